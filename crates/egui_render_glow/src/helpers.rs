@@ -179,6 +179,19 @@ pub unsafe fn create_samplers(glow_context: &glow::Context) -> (Sampler, Sampler
             .expect("failed to fit NEAREST in i32"),
     );
     glow_error!(glow_context);
+    glow_context.sampler_parameter_i32(
+        nearest_sampler,
+        glow::TEXTURE_WRAP_S,
+        glow::CLAMP_TO_EDGE as i32,
+    );
+    glow_error!(glow_context);
+
+    glow_context.sampler_parameter_i32(
+        nearest_sampler,
+        glow::TEXTURE_WRAP_T,
+        glow::CLAMP_TO_EDGE as i32,
+    );
+    glow_error!(glow_context);
 
     let font_sampler = glow_context
         .create_sampler()
@@ -239,15 +252,56 @@ pub unsafe fn create_samplers(glow_context: &glow::Context) -> (Sampler, Sampler
             .expect("failed to fit LINEAR MIPMAP NEAREST in i32"),
     );
     glow_error!(glow_context);
-    glow_context.sampler_parameter_i32(linear_sampler, glow::TEXTURE_WRAP_S, glow::REPEAT as i32);
+    glow_context.sampler_parameter_i32(
+        linear_sampler,
+        glow::TEXTURE_WRAP_S,
+        glow::CLAMP_TO_EDGE as i32,
+    );
     glow_error!(glow_context);
 
-    glow_context.sampler_parameter_i32(linear_sampler, glow::TEXTURE_WRAP_T, glow::REPEAT as i32);
+    glow_context.sampler_parameter_i32(
+        linear_sampler,
+        glow::TEXTURE_WRAP_T,
+        glow::CLAMP_TO_EDGE as i32,
+    );
     glow_error!(glow_context);
 
     (linear_sampler, nearest_sampler, font_sampler)
 }
 
+/// creates a sampler with filtering and wrapping matching the egui [`egui::TextureOptions`]
+pub unsafe fn create_sampler_from_texture_options(
+    glow_context: &glow::Context,
+    options: egui::TextureOptions,
+) -> Sampler {
+    let filter = |filter: egui::TextureFilter| match filter {
+        egui::TextureFilter::Nearest => glow::NEAREST as i32,
+        egui::TextureFilter::Linear => glow::LINEAR as i32,
+    };
+    let wrap = match options.wrap_mode {
+        egui::TextureWrapMode::ClampToEdge => glow::CLAMP_TO_EDGE,
+        egui::TextureWrapMode::Repeat => glow::REPEAT,
+        egui::TextureWrapMode::MirroredRepeat => glow::MIRRORED_REPEAT,
+    } as i32;
+    let sampler = glow_context
+        .create_sampler()
+        .expect("failed to create sampler");
+    glow_context.sampler_parameter_i32(
+        sampler,
+        glow::TEXTURE_MAG_FILTER,
+        filter(options.magnification),
+    );
+    glow_context.sampler_parameter_i32(
+        sampler,
+        glow::TEXTURE_MIN_FILTER,
+        filter(options.minification),
+    );
+    glow_context.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_S, wrap);
+    glow_context.sampler_parameter_i32(sampler, glow::TEXTURE_WRAP_T, wrap);
+    glow_error!(glow_context);
+    sampler
+}
+
 /// This is a simple default debug callback.
 /// It will just log the message using tracing.
 /// For the following severities, it will use the respective log levels
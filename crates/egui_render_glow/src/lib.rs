@@ -1,6 +1,8 @@
 mod helpers;
 use bytemuck::cast_slice;
 use egui::ahash::HashMap;
+use egui::util::IdTypeMap;
+use egui::PaintCallbackInfo;
 use egui::TextureId;
 use egui::TexturesDelta;
 pub use glow;
@@ -26,6 +28,28 @@ macro_rules! glow_error {
         }
     };
 }
+type PrepareCallback = dyn Fn(&glow::Context, &mut IdTypeMap) + Sync + Send;
+type PaintCallback = dyn Fn(PaintCallbackInfo, &glow::Context, &IdTypeMap) + Sync + Send;
+
+/// The callback of [`egui::PaintCallback`] for the glow painter. same as the `CallbackFn` of the wgpu painter.
+///
+/// `prepare` is called during [`Painter::prepare_render`], so you can upload data (or create gl objects) and store them in [`Painter::custom_data`].
+/// `paint` is called during [`Painter::render_egui`] with the scissor set to the clip rect and the viewport set to the callback rect.
+/// egui's gl state (program, buffers, blending, viewport etc..) is restored after `paint`, so you can change any state you want.
+pub struct CallbackFn {
+    pub prepare: Arc<PrepareCallback>,
+    pub paint: Arc<PaintCallback>,
+}
+
+impl Default for CallbackFn {
+    fn default() -> Self {
+        CallbackFn {
+            prepare: Arc::new(|_, _| ()),
+            paint: Arc::new(|_, _, _| ()),
+        }
+    }
+}
+
 /// All shaders are targeting #version 300 es
 pub const EGUI_VS: &str = include_str!("../egui.vert");
 /// output will be in linear space, so make suer to enable framebuffer srgb
@@ -79,8 +103,41 @@ pub struct GlowBackend {
     /// size of the framebuffer
     /// call resize framebuffer so that we can resize viewport
     pub framebuffer_size: [u32; 2],
+    /// color used to clear the framebuffer in `prepare_frame`. default is transparent.
+    /// This is in srgb (gamma) space with premultiplied alpha, same as egui's colors.
+    /// `FRAMEBUFFER_SRGB` is disabled during the clear, so the color is written to the framebuffer as is.
+    pub clear_color: [f32; 4],
+    /// If set, egui is only rendered inside this rectangle (in logical points, relative to the window).
+    /// The clip rects of all egui meshes are intersected with this, so the scissor skips the rest of the framebuffer.
+    /// useful to save fill rate, if egui only draws in a corner of a large transparent window (picture-in-picture).
+    /// egui's coordinates (and input) remain window-relative. see [`Self::set_render_viewport`]
+    pub render_viewport: Option<egui::Rect>,
+    /// opengl viewport `[x, y, width, height]` in physical pixels (bottom left origin), applied before rendering egui.
+    /// `None` uses the whole framebuffer. This is independent of [`Self::framebuffer_size`], which is still used for scissor math.
+    /// see [`Self::set_viewport`]
+    pub gl_viewport: Option<[i32; 4]>,
+    /// If greater than 1.0, egui is rendered into an offscreen framebuffer this many times larger than the window,
+    /// which is then downsampled onto the window framebuffer. This makes the ui crisper without MSAA.
+    /// The egui context must also use a pixels_per_point multiplied by this factor (`egui_overlay` does this for you).
+    /// default is 1.0 (no supersampling). Not supported on wasm yet, as webgl can't disable srgb encoding for srgb framebuffers.
+    pub supersample_factor: f32,
+    /// offscreen target used when [`Self::supersample_factor`] is greater than 1.0. (re)created lazily in `render_egui`
+    pub supersample_target: Option<SupersampleTarget>,
     pub painter: Painter,
 }
+/// user texture id reserved for the [`SupersampleTarget`] texture.
+pub const SUPERSAMPLE_TEXTURE_ID: u64 = u64::MAX;
+/// Offscreen framebuffer used when egui is supersampled. see [`GlowBackend::supersample_factor`]
+///
+/// The texture is also inserted into [`Painter::user_textures`] with [`SUPERSAMPLE_TEXTURE_ID`],
+/// so that we can draw it onto the window framebuffer as a single textured quad using the egui program.
+pub struct SupersampleTarget {
+    pub fbo: glow::NativeFramebuffer,
+    /// `SRGB8_ALPHA8` texture attached to [`Self::fbo`]
+    pub texture: glow::NativeTexture,
+    /// size in physical pixels
+    pub size: [u32; 2],
+}
 
 impl Drop for GlowBackend {
     fn drop(&mut self) {
@@ -131,12 +188,26 @@ impl GlowBackend {
             glow_context,
             painter,
             framebuffer_size,
+            clear_color: [0.0; 4],
+            render_viewport: None,
+            gl_viewport: None,
+            supersample_factor: 1.0,
+            supersample_target: None,
         }
     }
+    /// see [`Self::render_viewport`]. `None` renders egui across the whole framebuffer.
+    pub fn set_render_viewport(&mut self, viewport: Option<egui::Rect>) {
+        self.render_viewport = viewport;
+    }
 
     pub fn prepare_frame(&mut self, _latest_framebuffer_size_getter: impl FnMut() -> [u32; 2]) {
         unsafe {
             self.glow_context.disable(glow::SCISSOR_TEST);
+            // make sure that the clear color is not affected by srgb state left over by previous draw calls
+            #[cfg(not(target_arch = "wasm32"))]
+            self.glow_context.disable(glow::FRAMEBUFFER_SRGB);
+            let [r, g, b, a] = self.clear_color;
+            self.glow_context.clear_color(r, g, b, a);
             self.glow_context
                 .clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
         }
@@ -145,18 +216,44 @@ impl GlowBackend {
     pub fn resize_framebuffer(&mut self, fb_size: [u32; 2]) {
         self.framebuffer_size = fb_size;
         self.painter.screen_size_physical = fb_size;
+        self.apply_viewport();
+    }
+    /// sets the opengl viewport without changing the framebuffer size used for scissor math.
+    /// useful when rendering to a sub-region of the window or to an offscreen target of a different size.
+    /// set [`Self::gl_viewport`] to `None` to go back to using the whole framebuffer.
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.gl_viewport = Some([x, y, width, height]);
+        self.apply_viewport();
+    }
+    /// applies [`Self::gl_viewport`] (or the whole framebuffer if it is `None`) to the opengl context
+    fn apply_viewport(&self) {
+        let [x, y, width, height] = self.gl_viewport.unwrap_or([
+            0,
+            0,
+            self.framebuffer_size[0] as i32,
+            self.framebuffer_size[1] as i32,
+        ]);
         unsafe {
-            self.glow_context
-                .viewport(0, 0, fb_size[0] as i32, fb_size[1] as i32);
+            self.glow_context.viewport(x, y, width, height);
         }
     }
 
     pub fn render_egui(
         &mut self,
-        meshes: Vec<egui::ClippedPrimitive>,
+        mut meshes: Vec<egui::ClippedPrimitive>,
         textures_delta: egui::TexturesDelta,
         logical_screen_size: [f32; 2],
     ) {
+        if let Some(viewport) = self.render_viewport {
+            for primitive in meshes.iter_mut() {
+                primitive.clip_rect = primitive.clip_rect.intersect(viewport);
+            }
+        }
+        self.apply_viewport();
+        if self.supersample_factor > 1.0 && cfg!(not(target_arch = "wasm32")) {
+            unsafe { self.render_egui_supersampled(meshes, textures_delta, logical_screen_size) };
+            return;
+        }
         unsafe {
             self.painter.prepare_render(
                 &self.glow_context,
@@ -167,6 +264,133 @@ impl GlowBackend {
             self.painter.render_egui(&self.glow_context);
         }
     }
+    /// uploads the textures of `textures_delta` without drawing anything. The freed textures are deleted after the next `render_egui`.
+    /// useful to upload the font atlas at startup, instead of during the first frame. see [`Painter::upload_textures`]
+    pub fn upload_textures(&mut self, textures_delta: egui::TexturesDelta) {
+        self.painter.textures_to_delete.extend(textures_delta.free);
+        unsafe {
+            self.painter
+                .upload_textures(&self.glow_context, textures_delta.set);
+        }
+    }
+    /// renders egui into the [`SupersampleTarget`], and then draws it onto the window framebuffer.
+    unsafe fn render_egui_supersampled(
+        &mut self,
+        meshes: Vec<egui::ClippedPrimitive>,
+        textures_delta: egui::TexturesDelta,
+        logical_screen_size: [f32; 2],
+    ) {
+        let framebuffer_size = self.framebuffer_size;
+        let size =
+            framebuffer_size.map(|s| ((s as f32 * self.supersample_factor).round() as u32).max(1));
+        self.ensure_supersample_target(size);
+        let gl = &self.glow_context;
+        let fbo = self.supersample_target.as_ref().map(|target| target.fbo);
+        // render egui into the offscreen framebuffer
+        gl.bind_framebuffer(glow::FRAMEBUFFER, fbo);
+        gl.viewport(0, 0, size[0] as i32, size[1] as i32);
+        gl.disable(glow::SCISSOR_TEST);
+        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        gl.clear(glow::COLOR_BUFFER_BIT);
+        self.painter.screen_size_physical = size;
+        self.painter
+            .prepare_render(gl, meshes, textures_delta, logical_screen_size);
+        self.painter.render_egui(gl);
+        // draw it onto the window framebuffer
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        self.apply_viewport();
+        self.painter.screen_size_physical = framebuffer_size;
+        let screen_rect = egui::Rect::from_min_size(Default::default(), logical_screen_size.into());
+        let mut mesh = egui::Mesh::with_texture(TextureId::User(SUPERSAMPLE_TEXTURE_ID));
+        // opengl textures are bottom up, so we flip the uv
+        mesh.add_rect_with_uv(
+            screen_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 1.0), egui::pos2(1.0, 0.0)),
+            egui::Color32::WHITE,
+        );
+        self.painter.prepare_render(
+            gl,
+            vec![egui::ClippedPrimitive {
+                clip_rect: screen_rect,
+                primitive: egui::epaint::Primitive::Mesh(mesh),
+            }],
+            Default::default(),
+            logical_screen_size,
+        );
+        self.painter.render_egui(gl);
+    }
+    /// (re)creates the supersample target if its size doesn't match `size`
+    unsafe fn ensure_supersample_target(&mut self, size: [u32; 2]) {
+        if self
+            .supersample_target
+            .as_ref()
+            .is_some_and(|target| target.size == size)
+            // painter might have been reset
+            && self
+                .painter
+                .user_textures
+                .contains_key(&SUPERSAMPLE_TEXTURE_ID)
+        {
+            return;
+        }
+        let gl = &self.glow_context;
+        if let Some(old) = self.supersample_target.take() {
+            // if the painter was reset (eg: after a context loss), the old handles belong to the dead context.
+            // deleting them might delete live objects of the new context with the same names. so, we just drop them.
+            if self
+                .painter
+                .user_textures
+                .remove(&SUPERSAMPLE_TEXTURE_ID)
+                .is_some()
+            {
+                gl.delete_framebuffer(old.fbo);
+                gl.delete_texture(old.texture);
+            }
+        }
+        debug!(?size, "creating egui supersample target");
+        let texture = gl
+            .create_texture()
+            .expect("failed to create supersample texture");
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::SRGB8_ALPHA8 as i32,
+            size[0] as i32,
+            size[1] as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            None,
+        );
+        let fbo = gl
+            .create_framebuffer()
+            .expect("failed to create supersample framebuffer");
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(texture),
+            0,
+        );
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            tracing::error!(status, "supersample framebuffer is incomplete");
+        }
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        glow_error!(gl);
+        self.painter.user_textures.insert(
+            SUPERSAMPLE_TEXTURE_ID,
+            GpuTexture {
+                handle: texture,
+                width: size[0],
+                height: size[1],
+                sampler: self.painter.linear_sampler,
+            },
+        );
+        self.supersample_target = Some(SupersampleTarget { fbo, texture, size });
+    }
 }
 pub struct GpuTexture {
     pub handle: glow::NativeTexture,
@@ -174,6 +398,16 @@ pub struct GpuTexture {
     pub height: u32,
     pub sampler: NativeSampler,
 }
+/// color space of the pixels uploaded with [`Painter::create_user_texture`].
+/// egui's own managed textures are always srgb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// pixels are srgb encoded (like most images). uploaded as `SRGB8_ALPHA8`, so that sampling converts them to linear.
+    #[default]
+    Srgb,
+    /// pixels are already linear (eg: normal maps or data textures). uploaded as `RGBA8`, to avoid applying gamma twice.
+    Linear,
+}
 
 /// Egui Painter using glow::Context
 /// Assumptions:
@@ -185,7 +419,13 @@ pub struct Painter {
     pub linear_sampler: Sampler,
     pub nearest_sampler: Sampler,
     pub font_sampler: Sampler,
+    /// samplers for textures which want a wrap mode other than clamp to edge (eg: tiling textures),
+    /// or different magnification/minification filters (eg: pixel art scaled up with nearest, but smoothed down with linear).
+    /// created lazily when a texture with such [`egui::TextureOptions`] is uploaded.
+    pub custom_samplers: HashMap<egui::TextureOptions, Sampler>,
     pub managed_textures: HashMap<u64, GpuTexture>,
+    /// textures created by user. see [`Self::create_user_texture`]
+    pub user_textures: HashMap<u64, GpuTexture>,
     pub egui_program: Program,
     pub vao: VertexArray,
     pub vbo: Buffer,
@@ -198,6 +438,8 @@ pub struct Painter {
     pub logical_screen_size: [f32; 2],
     /// must update on framebuffer resize.
     pub screen_size_physical: [u32; 2],
+    /// storage for the data of paint callbacks (gl objects etc..) between frames. see [`CallbackFn`]
+    pub custom_data: IdTypeMap,
 }
 
 impl Painter {
@@ -245,6 +487,7 @@ impl Painter {
             debug!("created linear and nearest samplers");
             Self {
                 managed_textures: Default::default(),
+                user_textures: Default::default(),
                 egui_program,
                 vao,
                 vbo,
@@ -252,15 +495,123 @@ impl Painter {
                 linear_sampler,
                 nearest_sampler,
                 font_sampler,
+                custom_samplers: Default::default(),
                 u_screen_size,
                 u_sampler,
                 clipped_primitives: Vec::new(),
                 textures_to_delete: Vec::new(),
                 logical_screen_size: [0.0; 2],
                 screen_size_physical: [0; 2],
+                custom_data: IdTypeMap::default(),
+            }
+        }
+    }
+    /// sampler to use for a (non-font) texture with these options.
+    /// the default linear/nearest samplers are reused if possible. otherwise, a custom sampler is created (and cached).
+    /// # Safety
+    /// opengl context must be current
+    pub unsafe fn sampler_for_options(
+        &mut self,
+        glow_context: &glow::Context,
+        options: egui::TextureOptions,
+    ) -> Sampler {
+        if options.wrap_mode != egui::TextureWrapMode::ClampToEdge
+            || options.magnification != options.minification
+        {
+            *self
+                .custom_samplers
+                .entry(options)
+                .or_insert_with(|| create_sampler_from_texture_options(glow_context, options))
+        } else {
+            match options.minification {
+                egui::TextureFilter::Nearest => self.nearest_sampler,
+                egui::TextureFilter::Linear => self.linear_sampler,
             }
         }
     }
+    /// Creates a texture from the rgba `bytes` and inserts it into [`Self::user_textures`] with `id`.
+    /// The texture is `SRGB8_ALPHA8` or `RGBA8` based on `color_space`.
+    /// You can then use `TextureId::User(id)` with egui images to draw it.
+    ///
+    /// `size` is [width, height] in pixels and `bytes` must be tightly packed rows of 4 bytes per pixel.
+    /// If a user texture with `id` already exists, it will be deleted and replaced.
+    /// # Safety
+    /// opengl context must be current
+    pub unsafe fn create_user_texture(
+        &mut self,
+        glow_context: &glow::Context,
+        id: u64,
+        bytes: &[u8],
+        size: [u32; 2],
+        options: egui::TextureOptions,
+        color_space: ColorSpace,
+    ) {
+        let sampler = self.sampler_for_options(glow_context, options);
+        let internal_format = match color_space {
+            ColorSpace::Srgb => glow::SRGB8_ALPHA8,
+            ColorSpace::Linear => glow::RGBA8,
+        };
+        let handle = glow_context
+            .create_texture()
+            .expect("failed to create user texture");
+        glow_context.bind_texture(glow::TEXTURE_2D, Some(handle));
+        glow_context.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            internal_format as i32,
+            size[0] as i32,
+            size[1] as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(bytes),
+        );
+        glow_error!(glow_context);
+        if let Some(old) = self.user_textures.insert(
+            id,
+            GpuTexture {
+                handle,
+                width: size[0],
+                height: size[1],
+                sampler,
+            },
+        ) {
+            glow_context.delete_texture(old.handle);
+        }
+    }
+    /// Writes the rgba `bytes` into the existing user texture with `id` using `tex_sub_image_2d`, without recreating the texture.
+    /// This is meant for textures which change every frame, like a video or screen capture feed.
+    ///
+    /// `size` is [width, height] in pixels and must fit inside the texture. `bytes` must be tightly packed rows of 4 bytes per pixel.
+    ///
+    /// returns false if there's no user texture with `id`.
+    /// # Safety
+    /// opengl context must be current
+    pub unsafe fn update_user_texture(
+        &mut self,
+        glow_context: &glow::Context,
+        id: u64,
+        bytes: &[u8],
+        size: [u32; 2],
+    ) -> bool {
+        let Some(texture) = self.user_textures.get(&id) else {
+            return false;
+        };
+        glow_context.bind_texture(glow::TEXTURE_2D, Some(texture.handle));
+        glow_context.tex_sub_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            0,
+            0,
+            size[0] as i32,
+            size[1] as i32,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(bytes),
+        );
+        glow_error!(glow_context);
+        true
+    }
     /// uploads data to opengl buffers / textures
     /// # Safety
     /// make sure that there's no opengl issues and context is still current
@@ -271,17 +622,31 @@ impl Painter {
         textures_delta: TexturesDelta,
         logical_screen_size: [f32; 2],
     ) {
-        self.textures_to_delete = textures_delta.free;
+        self.textures_to_delete.extend(textures_delta.free);
         self.clipped_primitives = meshes;
         self.logical_screen_size = logical_screen_size;
         glow_error!(glow_context);
-
-        // update textures
-        for (texture_id, delta) in textures_delta.set {
-            let sampler = match delta.options.minification {
-                egui::TextureFilter::Nearest => self.nearest_sampler,
-                egui::TextureFilter::Linear => self.linear_sampler,
-            };
+        self.upload_textures(glow_context, textures_delta.set);
+        for clipped_primitive in &self.clipped_primitives {
+            if let egui::epaint::Primitive::Callback(cb) = &clipped_primitive.primitive {
+                (cb.callback
+                    .downcast_ref::<CallbackFn>()
+                    .expect("failed to downcast egui callback fn")
+                    .prepare)(glow_context, &mut self.custom_data);
+            }
+        }
+    }
+    /// creates/updates the textures of the egui texture delta, without drawing anything.
+    /// `prepare_render` calls this for you, but you can use this to upload textures ahead of time (eg: font atlas at startup).
+    /// # Safety
+    /// make sure that there's no opengl issues and context is still current
+    pub unsafe fn upload_textures(
+        &mut self,
+        glow_context: &glow::Context,
+        textures_delta_set: Vec<(TextureId, egui::epaint::ImageDelta)>,
+    ) {
+        for (texture_id, delta) in textures_delta_set {
+            let sampler = self.sampler_for_options(glow_context, delta.options);
             match texture_id {
                 TextureId::Managed(managed) => {
                     glow_context.bind_texture(
@@ -370,36 +735,10 @@ impl Painter {
         let screen_size_physical = self.screen_size_physical;
         let screen_size_logical = self.logical_screen_size;
         let scale = screen_size_physical[0] as f32 / screen_size_logical[0];
-
-        // setup egui configuration
-        glow_context.enable(glow::SCISSOR_TEST);
-        glow_context.disable(glow::DEPTH_TEST);
-        glow_error!(glow_context);
-        #[cfg(not(target_arch = "wasm32"))]
-        glow_context.disable(glow::FRAMEBUFFER_SRGB);
-
-        glow_error!(glow_context);
-        glow_context.active_texture(glow::TEXTURE0);
-        glow_error!(glow_context);
-
-        glow_context.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
-        glow_context.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
-        glow_context.bind_vertex_array(Some(self.vao));
-        glow_context.enable(glow::BLEND);
-        glow_context.blend_equation_separate(glow::FUNC_ADD, glow::FUNC_ADD);
-        glow_context.blend_func_separate(
-            // egui outputs colors with premultiplied alpha:
-            glow::ONE,
-            glow::ONE_MINUS_SRC_ALPHA,
-            // Less important, but this is technically the correct alpha blend function
-            // when you want to make use of the framebuffer alpha (for screenshots, compositing, etc).
-            glow::ONE_MINUS_DST_ALPHA,
-            glow::ONE,
-        );
-        glow_context.use_program(Some(self.egui_program));
-        glow_context.active_texture(glow::TEXTURE0);
-        glow_context.uniform_1_i32(Some(&self.u_sampler), 0);
-        glow_context.uniform_2_f32_slice(Some(&self.u_screen_size), &screen_size_logical);
+        // paint callbacks change the viewport. so, we restore it after each callback.
+        let mut viewport = [0i32; 4];
+        glow_context.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+        self.set_egui_gl_state(glow_context);
         for clipped_primitive in &self.clipped_primitives {
             if let Some(scissor_rect) = scissor_from_clip_rect_opengl(
                 &clipped_primitive.clip_rect,
@@ -417,46 +756,33 @@ impl Painter {
             }
             match clipped_primitive.primitive {
                 egui::epaint::Primitive::Mesh(ref mesh) => {
-                    glow_context.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
-                    glow_context.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
-                    glow_context.buffer_data_u8_slice(
-                        glow::ARRAY_BUFFER,
-                        cast_slice(&mesh.vertices),
-                        glow::STREAM_DRAW,
+                    self.draw_mesh(glow_context, mesh);
+                }
+                egui::epaint::Primitive::Callback(ref cb) => {
+                    let rect = cb.rect;
+                    // opengl viewport origin is bottom left
+                    glow_context.viewport(
+                        viewport[0] + (rect.min.x * scale).round() as i32,
+                        viewport[1] + viewport[3] - (rect.max.y * scale).round() as i32,
+                        (rect.width() * scale).round() as i32,
+                        (rect.height() * scale).round() as i32,
                     );
-                    glow_context.buffer_data_u8_slice(
-                        glow::ELEMENT_ARRAY_BUFFER,
-                        cast_slice(&mesh.indices),
-                        glow::STREAM_DRAW,
+                    (cb.callback
+                        .downcast_ref::<CallbackFn>()
+                        .expect("failed to downcast egui callback fn")
+                        .paint)(
+                        PaintCallbackInfo {
+                            viewport: rect,
+                            clip_rect: clipped_primitive.clip_rect,
+                            pixels_per_point: scale,
+                            screen_size_px: screen_size_physical,
+                        },
+                        glow_context,
+                        &self.custom_data,
                     );
-                    glow_error!(glow_context);
-                    match mesh.texture_id {
-                        TextureId::Managed(managed) => {
-                            let managed_tex = self
-                                .managed_textures
-                                .get(&managed)
-                                .expect("managed texture cannot be found");
-                            glow_context.bind_texture(glow::TEXTURE_2D, Some(managed_tex.handle));
-
-                            glow_context.bind_sampler(0, Some(managed_tex.sampler));
-                        }
-                        TextureId::User(_) => todo!(),
-                    }
-                    glow_error!(glow_context);
-
-                    let indices_len: i32 = mesh
-                        .indices
-                        .len()
-                        .try_into()
-                        .expect("failed to fit indices length into i32");
-
-                    glow_error!(glow_context);
-                    glow_context.draw_elements(glow::TRIANGLES, indices_len, glow::UNSIGNED_INT, 0);
-
-                    glow_error!(glow_context);
+                    glow_context.viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+                    self.set_egui_gl_state(glow_context);
                 }
-
-                egui::epaint::Primitive::Callback(_) => todo!(),
             }
         }
         glow_error!(glow_context);
@@ -471,11 +797,117 @@ impl Painter {
                             .handle,
                     );
                 }
-                TextureId::User(_) => todo!(),
+                TextureId::User(user) => {
+                    if let Some(texture) = self.user_textures.remove(&user) {
+                        glow_context.delete_texture(texture.handle);
+                    }
+                }
             }
         }
         glow_error!(glow_context);
     }
+    /// sets the gl state (program, buffers, blending etc..) required to draw egui meshes.
+    /// # Safety
+    /// opengl context must be current
+    unsafe fn set_egui_gl_state(&self, glow_context: &glow::Context) {
+        let screen_size_logical = self.logical_screen_size;
+        // setup egui configuration
+        glow_context.enable(glow::SCISSOR_TEST);
+        glow_context.disable(glow::DEPTH_TEST);
+        glow_error!(glow_context);
+        #[cfg(not(target_arch = "wasm32"))]
+        glow_context.disable(glow::FRAMEBUFFER_SRGB);
+
+        glow_error!(glow_context);
+        glow_context.active_texture(glow::TEXTURE0);
+        glow_error!(glow_context);
+
+        glow_context.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        glow_context.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
+        glow_context.bind_vertex_array(Some(self.vao));
+        glow_context.enable(glow::BLEND);
+        glow_context.blend_equation_separate(glow::FUNC_ADD, glow::FUNC_ADD);
+        glow_context.blend_func_separate(
+            // egui outputs colors with premultiplied alpha:
+            glow::ONE,
+            glow::ONE_MINUS_SRC_ALPHA,
+            // Less important, but this is technically the correct alpha blend function
+            // when you want to make use of the framebuffer alpha (for screenshots, compositing, etc).
+            glow::ONE_MINUS_DST_ALPHA,
+            glow::ONE,
+        );
+        glow_context.use_program(Some(self.egui_program));
+        glow_context.active_texture(glow::TEXTURE0);
+        glow_context.uniform_1_i32(Some(&self.u_sampler), 0);
+        glow_context.uniform_2_f32_slice(Some(&self.u_screen_size), &screen_size_logical);
+    }
+    /// uploads the mesh into the egui buffers and draws it with its texture.
+    /// # Safety
+    /// opengl context must be current and egui gl state must be set with [`Self::set_egui_gl_state`]
+    unsafe fn draw_mesh(&self, glow_context: &glow::Context, mesh: &egui::Mesh) {
+        glow_context.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        glow_context.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
+        glow_context.buffer_data_u8_slice(
+            glow::ARRAY_BUFFER,
+            cast_slice(&mesh.vertices),
+            glow::STREAM_DRAW,
+        );
+        glow_context.buffer_data_u8_slice(
+            glow::ELEMENT_ARRAY_BUFFER,
+            cast_slice(&mesh.indices),
+            glow::STREAM_DRAW,
+        );
+        glow_error!(glow_context);
+        match mesh.texture_id {
+            TextureId::Managed(managed) => {
+                let managed_tex = self
+                    .managed_textures
+                    .get(&managed)
+                    .expect("managed texture cannot be found");
+                glow_context.bind_texture(glow::TEXTURE_2D, Some(managed_tex.handle));
+
+                glow_context.bind_sampler(0, Some(managed_tex.sampler));
+            }
+            TextureId::User(user) => {
+                let user_tex = self
+                    .user_textures
+                    .get(&user)
+                    .expect("user texture cannot be found");
+                glow_context.bind_texture(glow::TEXTURE_2D, Some(user_tex.handle));
+                glow_context.bind_sampler(0, Some(user_tex.sampler));
+            }
+        }
+        glow_error!(glow_context);
+
+        let indices_len: i32 = mesh
+            .indices
+            .len()
+            .try_into()
+            .expect("failed to fit indices length into i32");
+
+        glow_error!(glow_context);
+        glow_context.draw_elements(glow::TRIANGLES, indices_len, glow::UNSIGNED_INT, 0);
+
+        glow_error!(glow_context);
+    }
+    /// Forgets all the opengl objects and creates the base objects (program, buffers, samplers etc..) again.
+    /// Call this after the opengl context is lost and recreated (eg: external monitor unplugged),
+    /// as the old textures/buffers belong to the dead context.
+    ///
+    /// The old objects are *not* deleted, because they might belong to a lost context.
+    /// If the old context is still alive, call [`Self::destroy`] before this to avoid leaking them.
+    ///
+    /// egui only uploads the font texture once. So, after this, make egui send the full font texture again
+    /// (eg: by calling [`egui::Context::set_fonts`]), so that the next frame can rebuild everything from egui's deltas.
+    /// user textures must be created again too. [`Self::custom_data`] is kept, but any gl objects inside it need to be recreated by you.
+    /// # Safety
+    /// `glow_context` must be the current (new) context
+    pub unsafe fn reset(&mut self, glow_context: &glow::Context) {
+        tracing::warn!("resetting egui glow painter");
+        let custom_data = std::mem::take(&mut self.custom_data);
+        *self = Self::new(glow_context);
+        self.custom_data = custom_data;
+    }
     /// # Safety
     /// This must be called only once.
     /// must not use it again because this destroys all the opengl objects.
@@ -483,9 +915,16 @@ impl Painter {
         tracing::warn!("destroying egui glow painter");
         glow_context.delete_sampler(self.linear_sampler);
         glow_context.delete_sampler(self.nearest_sampler);
+        glow_context.delete_sampler(self.font_sampler);
+        for (_, sampler) in std::mem::take(&mut self.custom_samplers) {
+            glow_context.delete_sampler(sampler);
+        }
         for (_, texture) in std::mem::take(&mut self.managed_textures) {
             glow_context.delete_texture(texture.handle);
         }
+        for (_, texture) in std::mem::take(&mut self.user_textures) {
+            glow_context.delete_texture(texture.handle);
+        }
         glow_context.delete_program(self.egui_program);
         glow_context.delete_vertex_array(self.vao);
         glow_context.delete_buffer(self.vbo);
@@ -42,6 +42,43 @@ impl ThreeDBackend {
         self.glow_backend
             .prepare_frame(latest_framebuffer_size_getter);
     }
+    /// the raw glow context (shared with three_d's [`Context`] and egui). use it for custom gl effects alongside three_d and egui.
+    ///
+    /// gl state is global. three_d doesn't restore the state it sets (blend, depth test, bound framebuffer/program etc..),
+    /// so don't assume any state when you draw, and restore whatever you change before egui or three_d draws again.
+    /// egui sets the state it needs at the start of `render_egui`.
+    pub fn glow_context(&self) -> &std::sync::Arc<glow::Context> {
+        &self.glow_backend.glow_context
+    }
+    /// size of the framebuffer in physical pixels.
+    /// The glow viewport is always set from this size, so use it for three_d viewports/cameras and render targets.
+    pub fn framebuffer_size_physical(&self) -> [u32; 2] {
+        self.glow_backend.framebuffer_size
+    }
+    /// screen size in logical points, used by the latest `render_egui` call. egui meshes are in this space.
+    pub fn screen_size_logical(&self) -> [f32; 2] {
+        self.glow_backend.painter.logical_screen_size
+    }
+    /// physical pixels per logical point, derived from the sizes of the latest `render_egui` call.
+    ///
+    /// The logical size is only known after egui is rendered. So, this is 1.0 for the first frame (before `render_egui`),
+    /// which would make a camera set up with it wrong at other scales. In that case, use the scale of the window backend
+    /// (eg: `GlfwBackend::scale`), or [`scale_from_sizes`] with the window's logical size.
+    pub fn scale(&self) -> f32 {
+        scale_from_sizes(self.framebuffer_size_physical(), self.screen_size_logical())
+    }
+    /// viewport covering the whole framebuffer (in physical pixels), so that 3d content aligns with egui at any scale.
+    pub fn viewport(&self) -> three_d::Viewport {
+        let [width, height] = self.framebuffer_size_physical();
+        three_d::Viewport::new_at_origo(width, height)
+    }
+    /// render target for the window framebuffer (in physical pixels).
+    pub fn screen_render_target(&self) -> three_d::RenderTarget<'_> {
+        let [width, height] = self.framebuffer_size_physical();
+        three_d::RenderTarget::screen(&self.context, width, height)
+    }
+    /// `logical_screen_size` is in points (eg: `window_size_logical` of the glfw backend),
+    /// while the framebuffer is rendered at [`Self::framebuffer_size_physical`].
     pub fn render_egui(
         &mut self,
         meshes: Vec<ClippedPrimitive>,
@@ -52,7 +89,63 @@ impl ThreeDBackend {
             .render_egui(meshes, textures_delta, logical_screen_size);
     }
 
+    /// see [`GlowBackend::supersample_factor`]
+    pub fn supersample_factor(&self) -> f32 {
+        self.glow_backend.supersample_factor
+    }
+    /// see [`GlowBackend::supersample_factor`]
+    pub fn set_supersample_factor(&mut self, factor: f32) {
+        self.glow_backend.supersample_factor = factor;
+    }
+    /// see [`GlowBackend::set_viewport`]
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.glow_backend.set_viewport(x, y, width, height);
+    }
+    /// see [`GlowBackend::upload_textures`]
+    pub fn upload_textures(&mut self, textures_delta: TexturesDelta) {
+        self.glow_backend.upload_textures(textures_delta);
+    }
+    /// see [`GlowBackend::set_render_viewport`]
+    pub fn set_render_viewport(&mut self, viewport: Option<egui::Rect>) {
+        self.glow_backend.set_render_viewport(viewport);
+    }
     pub fn resize_framebuffer(&mut self, fb_size: [u32; 2]) {
         self.glow_backend.resize_framebuffer(fb_size);
     }
 }
+
+/// physical pixels per logical point. 1.0 if the logical size is zero (eg: unknown yet).
+pub fn scale_from_sizes(framebuffer_size_physical: [u32; 2], screen_size_logical: [f32; 2]) -> f32 {
+    if screen_size_logical[0] > 0.0 {
+        framebuffer_size_physical[0] as f32 / screen_size_logical[0]
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framebuffer_viewport_matches_egui_full_screen_scissor_at_scale_2() {
+        let framebuffer_size = [1600, 1200];
+        let logical_size = [800.0, 600.0];
+        let scale = scale_from_sizes(framebuffer_size, logical_size);
+        assert_eq!(scale, 2.0);
+        // full screen 3d quad. the viewport used by `ThreeDBackend::viewport` (and the glow viewport)
+        let viewport = three_d::Viewport::new_at_origo(framebuffer_size[0], framebuffer_size[1]);
+        // full screen egui panel, in the physical pixels it is rasterized to
+        let panel = egui::Rect::from_min_size(Default::default(), logical_size.into());
+        let [x, y, width, height] = scissor_from_clip_rect_opengl(&panel, scale, framebuffer_size)
+            .expect("panel is visible");
+        assert_eq!(
+            [viewport.x, viewport.y],
+            [x as i32, y as i32],
+            "origins must match"
+        );
+        assert_eq!([viewport.width, viewport.height], [width, height]);
+        // before egui is rendered, the logical size is unknown
+        assert_eq!(scale_from_sizes(framebuffer_size, [0.0, 0.0]), 1.0);
+    }
+}
@@ -6,7 +6,13 @@ struct VertexOutput {
     @builtin(position) position: vec4<f32>,
 };
 
-@group(0) @binding(0) var<uniform> u_screen_size: vec4<f32>;
+// logical screen size, padded to 16 bytes. must match `SCREEN_SIZE_UNIFORM_SIZE` in painter.rs
+struct ScreenSize {
+    size: vec2<f32>,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> u_screen_size: ScreenSize;
 
 
 @vertex
@@ -17,8 +23,8 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     out.position = vec4<f32>(
-        2.0 * a_pos.x / u_screen_size.x - 1.0,
-        1.0 - 2.0 * a_pos.y / u_screen_size.y,
+        2.0 * a_pos.x / u_screen_size.size.x - 1.0,
+        1.0 - 2.0 * a_pos.y / u_screen_size.size.y,
         0.0,
         1.0,
     );
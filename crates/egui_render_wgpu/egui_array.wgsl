@@ -0,0 +1,28 @@
+// appended to egui.wgsl (reusing its vertex shader and color conversion functions).
+// draws user textures from a texture binding array. see `UserTextureArray` in texture_array.rs
+
+// index of the texture (in the binding array) of the current draw call
+struct ArrayIndex {
+    index: u32,
+};
+var<push_constant> u_array_index: ArrayIndex;
+
+// bindings 0 and 1 are used by the regular egui textures. so, we use different bindings to avoid clashing with them.
+@group(1) @binding(2) var r_tex_array: binding_array<texture_2d<f32>>;
+@group(1) @binding(3) var r_tex_array_sampler: sampler;
+
+@fragment
+fn fs_array_linear_output(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_linear = textureSample(r_tex_array[u_array_index.index], r_tex_array_sampler, in.tex_coord);
+    let tex_gamma = gamma_from_linear_rgba(tex_linear);
+    let out_color_gamma = in.color * tex_gamma;
+    return vec4<f32>(linear_from_srgb(out_color_gamma.rgb), out_color_gamma.a);
+}
+
+@fragment
+fn fs_array_srgb_output(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_linear = textureSample(r_tex_array[u_array_index.index], r_tex_array_sampler, in.tex_coord);
+    let tex_gamma = gamma_from_linear_rgba(tex_linear);
+    let out_color_gamma = in.color * tex_gamma;
+    return vec4<f32>(out_color_gamma);
+}
@@ -1,23 +1,57 @@
 mod painter;
+mod supersample;
 mod surface;
-use std::sync::Arc;
+#[cfg(test)]
+mod test_utils;
+mod texture_array;
+mod timer;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::{debug, info};
 use wgpu::*;
 
 pub use painter::*;
+pub use supersample::SupersampleTarget;
 pub use surface::SurfaceManager;
+pub use texture_array::*;
+pub use timer::GpuTimer;
 pub use wgpu;
 
 pub struct WgpuConfig {
     pub backends: Backends,
     pub power_preference: PowerPreference,
+    /// used to request the device. `memory_hints` decides how the backend allocates memory:
+    /// [`MemoryHints::Performance`] (default) favors speed, while [`MemoryHints::MemoryUsage`] keeps the memory footprint low
+    /// (useful for overlays running next to a game). see [`Self::set_memory_hints`]
     pub device_descriptor: DeviceDescriptor<'static>,
+    /// prepended to the labels of all the gpu objects we create (device, pipelines, buffers, encoders, passes etc..).
+    /// useful to tell apart the objects of the overlay from other wgpu components in gpu debugger captures (RenderDoc/PIX).
+    /// empty by default, which keeps the plain labels.
+    pub label_prefix: String,
     /// If not empty, We will try to iterate over this vector and use the first format that is supported by the surface.
     /// If this is empty or none of the formats in this vector are supported, we will just use the first supported format of the surface.
     pub surface_formats_priority: Vec<TextureFormat>,
     /// we will try to use this config if supported. otherwise, the surface recommended options will be used.   
     pub surface_config: SurfaceConfiguration,
     pub transparent_surface: Option<bool>,
+    /// load op of the egui render pass. default is [`LoadOp::Load`], which draws egui on top of the surface cleared in `prepare_frame`.
+    /// If egui is the only thing you draw, you can use [`LoadOp::Clear`] to clear within the egui pass (its color becomes [`WgpuBackend::clear_color`]).
+    /// In that case, the standalone clear pass (and its command encoder) in `prepare_frame` is skipped, as egui pass will clear the surface anyway.
+    /// Only use `Clear` for egui-only overlays, as anything drawn in [`WgpuBackend::command_encoders`] would be cleared by egui pass.
+    pub egui_load_op: LoadOp<Color>,
+    /// minor version of gles3 used by the gl backend. default is automatic.
+    /// override this if your ANGLE/GLES setup has issues with the automatically chosen version.
+    pub gles_minor_version: Gles3MinorVersion,
+    /// shader compiler used by the dx12 backend. default is fxc. use dxc for newer shader models (needs the dxc dlls).
+    pub dx12_shader_compiler: Dx12Compiler,
+}
+impl WgpuConfig {
+    /// sets the `memory_hints` of [`Self::device_descriptor`]
+    pub fn set_memory_hints(&mut self, memory_hints: MemoryHints) {
+        self.device_descriptor.memory_hints = memory_hints;
+    }
 }
 impl Default for WgpuConfig {
     fn default() -> Self {
@@ -30,6 +64,7 @@ impl Default for WgpuConfig {
                 required_limits: Limits::downlevel_defaults(),
                 memory_hints: MemoryHints::default(),
             },
+            label_prefix: String::new(),
             surface_config: SurfaceConfiguration {
                 usage: TextureUsages::RENDER_ATTACHMENT,
                 format: TextureFormat::Bgra8UnormSrgb,
@@ -42,6 +77,9 @@ impl Default for WgpuConfig {
             },
             surface_formats_priority: vec![],
             transparent_surface: Some(true),
+            egui_load_op: LoadOp::Load,
+            gles_minor_version: Gles3MinorVersion::Automatic,
+            dx12_shader_compiler: Default::default(),
         }
     }
 }
@@ -52,6 +90,8 @@ pub struct WgpuBackend {
     pub instance: Arc<Instance>,
     /// wgpu adapter
     pub adapter: Arc<Adapter>,
+    /// info of the chosen adapter (backend, device type, driver etc..). useful for diagnostics or bug reports
+    pub adapter_info: AdapterInfo,
     /// wgpu device.
     pub device: Arc<Device>,
     /// wgpu queue. if you have commands that you would like to submit, instead push them into `Self::command_encoders`
@@ -59,11 +99,51 @@ pub struct WgpuBackend {
     /// contains egui specific wgpu data like textures or buffers or pipelines etc..
     pub painter: EguiPainter,
     pub surface_manager: SurfaceManager,
-    /// this is where we store our command encoders. we will create one during the `prepare_frame` fn.
-    /// users can just use this. or create new encoders, and push them into this vec.
+    /// this is where users store their command encoders, which draw *under* egui.
     /// `wgpu::Queue::submit` is very expensive, so we will submit ALL command encoders at the same time during the `present_frame` method
     /// just before presenting the swapchain image (surface texture).
+    ///
+    /// The submission order is always: [`Self::clear_encoder`] -> [`Self::command_encoders`] -> [`Self::egui_encoder`] -> [`Self::over_encoders`].
+    /// So, compositing your content relative to egui doesn't depend on when you push your encoders within a frame.
     pub command_encoders: Vec<CommandEncoder>,
+    /// users can push their encoders here to draw *over* egui. see [`Self::command_encoders`] for the submission order.
+    pub over_encoders: Vec<CommandEncoder>,
+    /// the surface clear encoder created in `prepare_frame` (if the clear pass isn't skipped).
+    pub clear_encoder: Option<CommandEncoder>,
+    /// the egui encoder created in `render_egui`
+    pub egui_encoder: Option<CommandEncoder>,
+    /// measures the egui render pass using timestamp queries.
+    /// only `Some` if [`Features::TIMESTAMP_QUERY`] was enabled in [`WgpuConfig::device_descriptor`]
+    pub gpu_timer: Option<GpuTimer>,
+    /// gpu time taken by the egui render pass of a recent frame. updated in `prepare_frame` when the timestamps are read back.
+    /// always `None` if [`Self::gpu_timer`] is `None`
+    pub last_gpu_frame_time: Option<Duration>,
+    /// see [`WgpuConfig::egui_load_op`]
+    pub egui_load_op: LoadOp<Color>,
+    /// color used by the surface clear pass in `prepare_frame`. default is transparent. see [`Self::set_clear_color`]
+    pub clear_color: Color,
+    /// the time when the latest `present` call returned. `None` if we haven't presented yet.
+    ///
+    /// wgpu doesn't expose the actual presentation timing (when the frame is on screen) yet.
+    /// So, this is just when we handed the frame over to the compositor/driver.
+    /// With [`PresentMode::Fifo`], the frame usually appears on screen one or two vsyncs later.
+    /// With mailbox/immediate, it's closer to the actual display time, but the compositor (if any) can still add latency.
+    pub last_present_instant: Option<Instant>,
+    /// time between the latest two `present` calls. useful to predict when the next frame will be on screen,
+    /// eg: to align an overlay with a moving object in a game.
+    pub last_frame_interval: Option<Duration>,
+    /// If set, egui is only rendered inside this rectangle (in logical points, relative to the window).
+    /// The clip rects of all egui meshes are intersected with this, so the scissor skips the rest of the framebuffer.
+    /// useful to save fill rate, if egui only draws in a corner of a large transparent window (picture-in-picture).
+    /// egui's coordinates (and input) remain window-relative. see [`Self::set_render_viewport`]
+    pub render_viewport: Option<egui::Rect>,
+    /// If greater than 1.0, egui is rendered into an offscreen texture this many times larger than the surface,
+    /// which is then downsampled onto the surface. This makes the ui crisper without MSAA.
+    /// The egui context must also use a pixels_per_point multiplied by this factor (`egui_overlay` does this for you).
+    /// default is 1.0 (no supersampling).
+    pub supersample_factor: f32,
+    /// offscreen target used when [`Self::supersample_factor`] is greater than 1.0. (re)created lazily in `render_egui`
+    pub supersample_target: Option<SupersampleTarget>,
 }
 impl Drop for WgpuBackend {
     fn drop(&mut self) {
@@ -78,20 +158,13 @@ impl WgpuBackend {
         window: Option<Box<dyn WindowHandle>>,
         latest_fb_size: [u32; 2],
     ) -> Self {
-        let WgpuConfig {
-            power_preference,
-            device_descriptor,
-            surface_formats_priority,
-            surface_config,
-            backends,
-            transparent_surface,
-        } = config;
+        let backends = config.backends;
         debug!("using wgpu backends: {:?}", backends);
         let instance = Arc::new(Instance::new(InstanceDescriptor {
             backends,
-            dx12_shader_compiler: Default::default(),
+            dx12_shader_compiler: config.dx12_shader_compiler.clone(),
             flags: InstanceFlags::from_build_config(),
-            gles_minor_version: Gles3MinorVersion::Automatic,
+            gles_minor_version: config.gles_minor_version,
         }));
         debug!("iterating over all adapters");
         #[cfg(not(target_arch = "wasm32"))]
@@ -112,7 +185,7 @@ impl WgpuBackend {
         let adapter = Arc::new(
             instance
                 .request_adapter(&RequestAdapterOptions {
-                    power_preference,
+                    power_preference: config.power_preference,
                     force_fallback_adapter: false,
                     compatible_surface: surface.as_ref(),
                 })
@@ -121,15 +194,59 @@ impl WgpuBackend {
         );
 
         info!("chosen adapter details: {:?}", adapter.get_info());
+        info!(
+            "using memory hints: {:?}",
+            config.device_descriptor.memory_hints
+        );
+        let device_label = config
+            .device_descriptor
+            .label
+            .map(|label| format!("{}{label}", config.label_prefix));
         let (device, queue) = adapter
-            .request_device(&device_descriptor, Default::default())
+            .request_device(
+                &DeviceDescriptor {
+                    label: device_label.as_deref(),
+                    ..config.device_descriptor.clone()
+                },
+                Default::default(),
+            )
             .await
             .expect("failed to create wgpu device");
 
-        let device = Arc::new(device);
-        let queue = Arc::new(queue);
-
-        let surface_manager = SurfaceManager::new(
+        Self::from_existing(
+            instance,
+            adapter,
+            Arc::new(device),
+            Arc::new(queue),
+            surface,
+            config,
+            latest_fb_size,
+        )
+    }
+    /// creates the backend with the gpu objects of your app, instead of creating new ones.
+    /// useful to embed the overlay in a bigger wgpu app (eg: sharing textures with your own renderer) without duplicate devices.
+    ///
+    /// `surface` must be created with `instance` and be compatible with `adapter`.
+    /// The instance/adapter/device related fields of `config` (backends, power preference, device descriptor etc..) are ignored.
+    pub fn from_existing(
+        instance: Arc<Instance>,
+        adapter: Arc<Adapter>,
+        device: Arc<Device>,
+        queue: Arc<Queue>,
+        surface: Option<Surface<'static>>,
+        config: WgpuConfig,
+        latest_fb_size: [u32; 2],
+    ) -> Self {
+        let WgpuConfig {
+            surface_formats_priority,
+            surface_config,
+            transparent_surface,
+            egui_load_op,
+            label_prefix,
+            ..
+        } = config;
+        let adapter_info = adapter.get_info();
+        let mut surface_manager = SurfaceManager::new(
             None,
             transparent_surface,
             latest_fb_size,
@@ -141,19 +258,46 @@ impl WgpuBackend {
             surface_config,
         );
 
+        surface_manager.label_prefix = label_prefix.clone();
         debug!("device features: {:#?}", device.features());
         debug!("device limits: {:#?}", device.limits());
 
-        let painter = EguiPainter::new(&device, surface_manager.surface_config.format);
+        let gpu_timer = GpuTimer::new(&device, &queue, &label_prefix);
+        let painter = EguiPainter::with_options(
+            &device,
+            surface_manager.surface_config.format,
+            EguiPainterOptions {
+                label_prefix,
+                ..Default::default()
+            },
+        );
+        info!("gpu timestamp queries enabled: {}", gpu_timer.is_some());
 
         Self {
             instance,
             adapter,
+            adapter_info,
             device,
             queue,
             painter,
             command_encoders: Vec::new(),
+            over_encoders: Vec::new(),
+            clear_encoder: None,
+            egui_encoder: None,
             surface_manager,
+            gpu_timer,
+            last_gpu_frame_time: None,
+            egui_load_op,
+            // keep the clear color in sync with the egui pass, if it clears the surface
+            clear_color: match egui_load_op {
+                LoadOp::Clear(color) => color,
+                LoadOp::Load => Color::TRANSPARENT,
+            },
+            last_present_instant: None,
+            last_frame_interval: None,
+            render_viewport: None,
+            supersample_factor: 1.0,
+            supersample_target: None,
         }
     }
 }
@@ -166,6 +310,11 @@ impl WgpuBackend {
         pollster::block_on(Self::new_async(config, window, latest_fb_size))
     }
 
+    /// info of the chosen adapter. contains name, backend, device type and driver details.
+    pub fn adapter_info(&self) -> &AdapterInfo {
+        &self.adapter_info
+    }
+
     pub fn resume(
         &mut self,
         window: Option<Box<dyn WindowHandle>>,
@@ -180,95 +329,210 @@ impl WgpuBackend {
             &self.adapter,
             &self.device,
         );
-        self.painter.on_resume(
-            &self.device,
-            self.surface_manager
-                .surface_config
-                .view_formats
-                .first()
-                .copied()
-                .unwrap(),
-        );
+        // surface views are created with the surface config format, so the pipeline must target the same format.
+        // otherwise, we would pick the wrong fragment shader and convert colors twice (or not at all)
+        self.painter
+            .on_resume(&self.device, self.surface_manager.surface_config.format);
     }
 
+    /// the format of the surface texture views that egui renders into (the surface configuration's format).
+    pub fn surface_texture_format(&self) -> TextureFormat {
+        self.surface_manager.surface_config.format
+    }
     pub fn prepare_frame(&mut self, latest_framebuffer_size_getter: impl FnMut() -> [u32; 2]) {
+        if let Some(gpu_time) = self
+            .gpu_timer
+            .as_mut()
+            .and_then(|timer| timer.try_read(&self.device))
+        {
+            self.last_gpu_frame_time = Some(gpu_time);
+        }
         self.surface_manager
             .create_current_surface_texture_view(latest_framebuffer_size_getter, &self.device);
+        // the surface might have been configured by someone else (eg: a user provided surface with `from_existing`).
+        // egui pipeline must match the format of the surface view. Otherwise, wgpu will panic when rendering.
+        let surface_format = self.surface_texture_format();
+        if self.painter.surface_format != surface_format {
+            info!(
+                old = ?self.painter.surface_format,
+                new = ?surface_format,
+                "surface format changed. recreating egui pipeline"
+            );
+            self.painter.on_resume(&self.device, surface_format);
+        }
+        // no need to clear twice, if the egui pass clears the surface anyway
+        if matches!(self.egui_load_op, LoadOp::Clear(_)) {
+            return;
+        }
         if let Some(view) = self.surface_manager.surface_view.as_ref() {
             let mut ce = self
                 .device
                 .create_command_encoder(&CommandEncoderDescriptor {
-                    label: "surface clear ce".into(),
+                    label: Some(&self.painter.options.label("surface clear ce")),
                 });
             ce.begin_render_pass(&RenderPassDescriptor {
-                label: "surface clear rpass".into(),
+                label: Some(&self.painter.options.label("surface clear rpass")),
                 color_attachments: &[Some(RenderPassColorAttachment {
                     view,
                     resolve_target: None,
                     ops: Operations {
-                        load: LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        load: LoadOp::Clear(self.clear_color),
                         store: StoreOp::Store,
                     },
                 })],
                 ..Default::default()
             });
-            self.command_encoders.push(ce);
+            self.clear_encoder = Some(ce);
         }
     }
 
     pub fn render_egui(
         &mut self,
-        meshes: Vec<egui::ClippedPrimitive>,
+        mut meshes: Vec<egui::ClippedPrimitive>,
         textures_delta: egui::TexturesDelta,
         logical_screen_size: [f32; 2],
     ) {
+        if let Some(viewport) = self.render_viewport {
+            for primitive in meshes.iter_mut() {
+                primitive.clip_rect = primitive.clip_rect.intersect(viewport);
+            }
+        }
         let mut command_encoder = self
             .device
             .create_command_encoder(&CommandEncoderDescriptor {
-                label: Some("egui command encoder"),
+                label: Some(&self.painter.options.label("egui command encoder")),
             });
+        let surface_size = [
+            self.surface_manager.surface_config.width,
+            self.surface_manager.surface_config.height,
+        ];
+        let supersampling = self.supersample_factor > 1.0;
+        if supersampling {
+            self.ensure_supersample_target(surface_size);
+        } else {
+            self.supersample_target = None;
+        }
+        #[cfg(feature = "wgpu_validation")]
+        push_validation_scope(&self.device);
         let draw_calls = self.painter.upload_egui_data(
             &self.device,
             &self.queue,
             meshes,
             textures_delta,
             logical_screen_size,
-            [
-                self.surface_manager.surface_config.width,
-                self.surface_manager.surface_config.height,
-            ],
+            // egui must be rendered at the size of the texture it's drawn into
+            self.supersample_target
+                .as_ref()
+                .map(|target| target.size)
+                .unwrap_or(surface_size),
             &mut command_encoder,
         );
+        if let Some(target) = self.supersample_target.as_ref() {
+            target.update_quad(&self.queue, logical_screen_size);
+        }
+        #[cfg(feature = "wgpu_validation")]
+        pop_validation_scope(&self.device, "egui texture/buffer upload");
+        let surface_view = self
+            .surface_manager
+            .surface_view
+            .as_ref()
+            .expect("failed ot get surface view for egui render pass creation");
         {
+            let (view, load) = match self.supersample_target.as_ref() {
+                // the offscreen texture only contains egui. so, we always clear it.
+                Some(target) => (&target.view, LoadOp::Clear(Color::TRANSPARENT)),
+                None => (surface_view, self.egui_load_op),
+            };
             let mut egui_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
-                label: Some("egui render pass"),
+                label: Some(&self.painter.options.label("egui render pass")),
                 color_attachments: &[Some(RenderPassColorAttachment {
-                    view: self
-                        .surface_manager
-                        .surface_view
-                        .as_ref()
-                        .expect("failed ot get surface view for egui render pass creation"),
+                    view,
                     resolve_target: None,
                     ops: Operations {
-                        load: LoadOp::Load,
+                        load,
                         store: StoreOp::Store,
                     },
                 })],
+                timestamp_writes: self.gpu_timer.as_ref().map(GpuTimer::timestamp_writes),
                 ..Default::default()
             });
             self.painter
                 .draw_egui_with_renderpass(&mut egui_pass, draw_calls);
         }
-        self.command_encoders.push(command_encoder);
+        if let Some(target) = self.supersample_target.as_ref() {
+            let mut composite_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some(
+                    &self
+                        .painter
+                        .options
+                        .label("egui supersample composite pass"),
+                ),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: surface_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: self.egui_load_op,
+                        store: StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            target.composite(&self.painter, &mut composite_pass);
+        }
+        if let Some(timer) = self.gpu_timer.as_mut() {
+            timer.resolve(&mut command_encoder);
+        }
+        self.egui_encoder = Some(command_encoder);
     }
 
+    /// (re)creates the supersample target, if its size or format doesn't match the surface anymore.
+    fn ensure_supersample_target(&mut self, surface_size: [u32; 2]) {
+        let size =
+            surface_size.map(|s| ((s as f32 * self.supersample_factor).round() as u32).max(1));
+        let format = self.painter.surface_format;
+        if self
+            .supersample_target
+            .as_ref()
+            .is_some_and(|target| target.size == size && target.format == format)
+        {
+            return;
+        }
+        debug!(?size, ?format, "creating egui supersample target");
+        self.supersample_target = Some(SupersampleTarget::new(
+            &self.device,
+            &self.painter,
+            format,
+            size,
+        ));
+    }
+    /// throws away all the recorded encoders and the acquired surface texture of this frame, without presenting.
+    /// useful to skip a frame (eg: after a panic in the middle of the frame).
+    pub fn discard_frame(&mut self) {
+        self.clear_encoder = None;
+        self.command_encoders.clear();
+        self.egui_encoder = None;
+        self.over_encoders.clear();
+        self.surface_manager.surface_view = None;
+        self.surface_manager.surface_current_image = None;
+    }
     pub fn present(&mut self) {
         assert!(self.surface_manager.surface_view.is_some());
-        self.queue.submit(
-            std::mem::take(&mut self.command_encoders)
-                .into_iter()
-                .map(|encoder| encoder.finish()),
-        );
+        // errors in recorded commands (eg: egui draw calls) are reported when the encoders are finished/submitted
+        #[cfg(feature = "wgpu_validation")]
+        push_validation_scope(&self.device);
+        let encoders = self
+            .clear_encoder
+            .take()
+            .into_iter()
+            .chain(std::mem::take(&mut self.command_encoders))
+            .chain(self.egui_encoder.take())
+            .chain(std::mem::take(&mut self.over_encoders));
+        self.queue.submit(encoders.map(|encoder| encoder.finish()));
+        #[cfg(feature = "wgpu_validation")]
+        pop_validation_scope(&self.device, "command submission");
+        if let Some(timer) = self.gpu_timer.as_mut() {
+            timer.map_readback();
+        }
         {
             self.surface_manager
                 .surface_view
@@ -280,8 +544,96 @@ impl WgpuBackend {
             .take()
             .expect("failed to surface texture to preset")
             .present();
+        let now = Instant::now();
+        self.last_frame_interval = self
+            .last_present_instant
+            .map(|last| now.saturating_duration_since(last));
+        self.last_present_instant = Some(now);
     }
 
+    /// draws `view` over the whole surface (stretched to fit) and pushes the encoder into [`Self::command_encoders`].
+    /// So, it is drawn *under* egui, which makes compositing a background (eg: a captured game frame) with an egui hud easy.
+    /// Must be called between `prepare_frame` and `present`.
+    ///
+    /// The texture is sampled with the linear sampler and written as is. So, use a srgb view if the texture contains srgb colors
+    /// and the surface is srgb (the default).
+    ///
+    /// This doesn't record anything when [`WgpuConfig::egui_load_op`] is `Clear`, as egui pass would clear over the blit anyway.
+    pub fn blit_to_surface(&mut self, view: &TextureView) {
+        if matches!(self.egui_load_op, LoadOp::Clear(_)) {
+            tracing::debug!("skipping blit_to_surface, as the egui pass clears the surface");
+            return;
+        }
+        let Some(surface_view) = self.surface_manager.surface_view.as_ref() else {
+            tracing::warn!(
+                "blit_to_surface called without a surface view. call it after prepare_frame"
+            );
+            return;
+        };
+        let format = self.surface_manager.surface_config.format;
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&self.painter.options.label("surface blit bindgroup")),
+            layout: &self.painter.mipmap_bgl,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&self.painter.linear_sampler),
+                },
+            ],
+        });
+        let pipeline = self.painter.blit_pipeline(&self.device, format);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some(&self.painter.options.label("surface blit encoder")),
+            });
+        {
+            let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some(&self.painter.options.label("surface blit pass")),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: surface_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        self.command_encoders.push(encoder);
+    }
+    /// uploads the textures of `textures_delta` without drawing anything. see [`EguiPainter::upload_textures`]
+    /// useful to upload the font atlas at startup, instead of during the first frame.
+    pub fn upload_textures(&mut self, textures_delta: egui::TexturesDelta) {
+        self.painter
+            .upload_textures(&self.device, &self.queue, textures_delta);
+    }
+    /// storage passed to the `prepare`/`paint` functions of paint callbacks ([`CallbackFn`]).
+    /// use this to insert the resources your callbacks need (eg: pipelines, buffers) once at startup,
+    /// instead of lazily creating them inside `prepare`.
+    pub fn callback_resources(&mut self) -> &mut egui::util::IdTypeMap {
+        &mut self.painter.custom_data
+    }
+    /// sets the color that the surface is cleared with every frame. eg: for opaque overlays or to dim the background.
+    /// If egui pass clears the surface (see [`WgpuConfig::egui_load_op`]), its clear color is updated too.
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+        if let LoadOp::Clear(_) = self.egui_load_op {
+            self.egui_load_op = LoadOp::Clear(color);
+        }
+    }
+    /// see [`Self::render_viewport`]. `None` renders egui across the whole framebuffer.
+    pub fn set_render_viewport(&mut self, viewport: Option<egui::Rect>) {
+        self.render_viewport = viewport;
+    }
     pub fn resize_framebuffer(&mut self, latest_fb_size: [u32; 2]) {
         self.surface_manager
             .resize_framebuffer(&self.device, latest_fb_size);
@@ -335,3 +687,54 @@ pub fn scissor_from_clip_rect(
     // return only if scissor width/height are not zero. otherwise, no need for a scissor rect at all
     (width != 0 && height != 0).then_some([x, y, width, height])
 }
+
+/// captures validation errors, so that they can be logged with [`pop_validation_scope`] instead of panicking inside wgpu.
+#[cfg(feature = "wgpu_validation")]
+fn push_validation_scope(device: &Device) {
+    device.push_error_scope(ErrorFilter::Validation);
+}
+/// logs the validation error (if any) captured since the matching [`push_validation_scope`].
+/// `operation` is just used for context in the log.
+#[cfg(feature = "wgpu_validation")]
+fn pop_validation_scope(device: &Device, operation: &str) {
+    if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+        tracing::error!(operation, "wgpu validation error: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_pixel_eq, render_meshes, test_device, white_font_texture};
+
+    #[test]
+    #[ignore = "needs a gpu adapter"]
+    fn solid_rect_color() {
+        let (dev, queue) = test_device();
+        const SIZE: u32 = 4;
+        let color = egui::Color32::from_rgb(200, 100, 50);
+        // srgb framebuffer uses `fs_main_linear_output` and the linear one uses `fs_main_srgb_output`.
+        // either way, the stored pixel must be the egui color (which is srgb) without any double conversion.
+        for format in [TextureFormat::Rgba8UnormSrgb, TextureFormat::Rgba8Unorm] {
+            let mut painter = EguiPainter::new(&dev, format);
+            let rect =
+                egui::Rect::from_min_size(Default::default(), egui::vec2(SIZE as f32, SIZE as f32));
+            let mut mesh = egui::Mesh::default();
+            mesh.add_colored_rect(rect, color);
+            let pixels = render_meshes(
+                &dev,
+                &queue,
+                &mut painter,
+                format,
+                SIZE,
+                vec![egui::ClippedPrimitive {
+                    clip_rect: rect,
+                    primitive: egui::epaint::Primitive::Mesh(mesh),
+                }],
+                white_font_texture(),
+            );
+            let center = pixels[(SIZE * SIZE / 2 + SIZE / 2) as usize];
+            assert_pixel_eq(center, color.to_array());
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, num::NonZeroU64, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    num::NonZeroU64,
+    sync::Arc,
+};
 
 use bytemuck::cast_slice;
 use egui::{
@@ -8,6 +12,8 @@ use egui::{
 };
 use wgpu::*;
 
+use crate::UserTextureArray;
+
 pub struct EguiPainter {
     /// current capacity of vertex buffer
     pub vb_len: usize,
@@ -30,30 +36,72 @@ pub struct EguiPainter {
     pub surface_format: TextureFormat,
     /// egui render pipeline
     pub pipeline: RenderPipeline,
-    /// This is the sampler used for most textures that user uploads
+    /// options used to create [`Self::pipeline`]. kept, so that we can recreate the pipeline with the same options on format change.
+    pub options: EguiPainterOptions,
+    /// This is the sampler used for most textures that user uploads. clamps to edge to avoid bleeding at the borders.
     pub linear_sampler: Sampler,
     /// nearest sampler suitable for font textures (or any pixellated textures)
     pub nearest_sampler: Sampler,
     pub font_sampler: Sampler,
+    /// samplers for textures which want a wrap mode other than [`TextureWrapMode::ClampToEdge`] (eg: tiling textures),
+    /// or different magnification/minification filters.
+    /// These are created lazily when a texture with such [`TextureOptions`] is uploaded.
+    pub custom_samplers: HashMap<TextureOptions, Sampler>,
     /// Textures uploaded by egui itself.
     pub managed_textures: BTreeMap<u64, EguiTexture>,
     /// these are exposed to user so that they can edit them or insert any custom textures which aren't supported by egui like texture wrapping or array textures etc..
     pub user_textures: BTreeMap<u64, EguiTexture>,
+    /// user textures which are drawn from a single texture binding array. see [`Self::create_user_texture_array`]
+    pub user_texture_array: Option<UserTextureArray>,
     /// textures to free
     pub delete_textures: Vec<TextureId>,
     pub custom_data: IdTypeMap,
     pub mipmap_pipeline: RenderPipeline,
     pub mipmap_bgl: BindGroupLayout,
     pub mipmap_sampler: Sampler,
+    /// pipelines (using the mipmap blit shader) to draw a fullscreen texture into a target of the key format.
+    /// created lazily by [`Self::blit_pipeline`]
+    pub blit_pipelines: HashMap<TextureFormat, RenderPipeline>,
 }
 
 pub const EGUI_SHADER_SRC: &str = include_str!("../egui.wgsl");
 
-type PrepareCallback = dyn Fn(&Device, &Queue, &mut IdTypeMap) + Sync + Send;
+/// Options for creating the egui render pipeline. see [`EguiPainter::with_options`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EguiPainterOptions {
+    /// msaa sample count of the render pass that egui will be drawn in. default is 1.
+    /// If this is greater than 1, you must draw egui into a multisampled texture (with this sample count) yourself,
+    /// as [`crate::WgpuBackend`] always renders egui directly into the surface texture.
+    pub sample_count: u32,
+    /// blend state of the egui pipeline. default is [`EGUI_PIPELINE_BLEND_STATE`] (premultiplied alpha)
+    pub blend: BlendState,
+    /// prepended to the labels of all the gpu objects created by the painter. see [`crate::WgpuConfig::label_prefix`]
+    pub label_prefix: String,
+}
+impl Default for EguiPainterOptions {
+    fn default() -> Self {
+        Self {
+            sample_count: 1,
+            blend: EGUI_PIPELINE_BLEND_STATE,
+            label_prefix: String::new(),
+        }
+    }
+}
+impl EguiPainterOptions {
+    /// `name` with [`Self::label_prefix`] prepended
+    pub fn label(&self, name: &str) -> String {
+        format!("{}{name}", self.label_prefix)
+    }
+}
+
+type PrepareCallback = dyn Fn(&Device, &Queue, &mut CommandEncoder, &mut IdTypeMap) + Sync + Send;
 type RenderCallback =
     dyn for<'a, 'b> Fn(PaintCallbackInfo, &'a mut RenderPass<'b>, &'b IdTypeMap) + Sync + Send;
 
 pub struct CallbackFn {
+    /// called while uploading egui data, before the egui render pass is recorded.
+    /// The encoder is the one that egui render pass will be recorded into. So, any commands (eg: compute passes to simulate particles)
+    /// you record with it are executed before `paint` draws.
     pub prepare: Arc<PrepareCallback>,
     pub paint: Arc<RenderCallback>,
 }
@@ -61,7 +109,7 @@ pub struct CallbackFn {
 impl Default for CallbackFn {
     fn default() -> Self {
         CallbackFn {
-            prepare: Arc::new(|_, _, _| ()),
+            prepare: Arc::new(|_, _, _, _| ()),
             paint: Arc::new(|_, _, _| ()),
         }
     }
@@ -97,6 +145,8 @@ impl EguiPainter {
 
         rpass.set_vertex_buffer(0, self.vb.slice(..));
         rpass.set_index_buffer(self.ib.slice(..), IndexFormat::Uint32);
+        // whether the texture array pipeline is bound instead of the regular egui pipeline
+        let mut array_pipeline_bound = false;
         for draw_call in draw_calls {
             match draw_call {
                 EguiDrawCalls::Mesh {
@@ -112,6 +162,30 @@ impl EguiPainter {
                     // so, we instead bind the buffer with different offsets every call so that indices will point to their respective vertices.
                     // this is possible because webgl2 has bindBufferRange (which allows specifying a offset as the start of the buffer binding)
                     rpass.set_vertex_buffer(0, self.vb.slice(base_vertex as u64 * 20..));
+                    let array_texture = match texture_id {
+                        TextureId::User(key) => self
+                            .user_texture_array
+                            .as_ref()
+                            .and_then(|array| Some((array, *array.indices.get(&key)?))),
+                        TextureId::Managed(_) => None,
+                    };
+                    if let Some((array, index)) = array_texture {
+                        // the bindgroup stays bound between consecutive array textures. only the index changes.
+                        if !array_pipeline_bound {
+                            rpass.set_pipeline(&array.pipeline);
+                            rpass.set_bind_group(0, &self.screen_size_bind_group, &[]);
+                            rpass.set_bind_group(1, &array.bindgroup, &[]);
+                            array_pipeline_bound = true;
+                        }
+                        rpass.set_push_constants(ShaderStages::FRAGMENT, 0, &index.to_le_bytes());
+                        rpass.draw_indexed(index_start..index_end, 0, 0..1);
+                        continue;
+                    }
+                    if array_pipeline_bound {
+                        rpass.set_pipeline(&self.pipeline);
+                        rpass.set_bind_group(0, &self.screen_size_bind_group, &[]);
+                        array_pipeline_bound = false;
+                    }
                     match texture_id {
                         TextureId::Managed(key) => {
                             rpass.set_bind_group(
@@ -124,7 +198,17 @@ impl EguiPainter {
                                 &[],
                             );
                         }
-                        TextureId::User(_) => unimplemented!(),
+                        TextureId::User(key) => {
+                            rpass.set_bind_group(
+                                1,
+                                &self
+                                    .user_textures
+                                    .get(&key)
+                                    .expect("cannot find user texture")
+                                    .bindgroup,
+                                &[],
+                            );
+                        }
                     }
                     rpass.draw_indexed(index_start..index_end, 0, 0..1);
                 }
@@ -135,6 +219,11 @@ impl EguiPainter {
                 } => {
                     let [x, y, width, height] = clip_rect;
                     rpass.set_scissor_rect(x, y, width, height);
+                    if array_pipeline_bound {
+                        rpass.set_pipeline(&self.pipeline);
+                        rpass.set_bind_group(0, &self.screen_size_bind_group, &[]);
+                        array_pipeline_bound = false;
+                    }
                     (paint_callback
                         .callback
                         .downcast_ref::<CallbackFn>()
@@ -158,21 +247,22 @@ impl EguiPainter {
         pipeline_surface_format: TextureFormat,
         screen_size_bindgroup_layout: &BindGroupLayout,
         texture_bindgroup_layout: &BindGroupLayout,
+        options: &EguiPainterOptions,
     ) -> RenderPipeline {
         // pipeline layout. screensize uniform buffer for vertex shader + texture and sampler for fragment shader
         let egui_pipeline_layout = dev.create_pipeline_layout(&PipelineLayoutDescriptor {
-            label: Some("egui pipeline layout"),
+            label: Some(&options.label("egui pipeline layout")),
             bind_group_layouts: &[screen_size_bindgroup_layout, texture_bindgroup_layout],
             push_constant_ranges: &[],
         });
         // shader from the wgsl source.
         let shader_module = dev.create_shader_module(ShaderModuleDescriptor {
-            label: Some("egui shader src"),
+            label: Some(&options.label("egui shader src")),
             source: ShaderSource::Wgsl(EGUI_SHADER_SRC.into()),
         });
         // create pipeline using shaders + pipeline layout
         dev.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("egui pipeline"),
+            label: Some(&options.label("egui pipeline")),
             layout: Some(&egui_pipeline_layout),
             vertex: VertexState {
                 module: &shader_module,
@@ -185,8 +275,10 @@ impl EguiPainter {
             },
             primitive: EGUI_PIPELINE_PRIMITIVE_STATE,
             depth_stencil: None,
-            // support multi sampling in future?
-            multisample: MultisampleState::default(),
+            multisample: MultisampleState {
+                count: options.sample_count,
+                ..Default::default()
+            },
             fragment: Some(FragmentState {
                 module: &shader_module,
                 entry_point: Some(if pipeline_surface_format.is_srgb() {
@@ -196,7 +288,7 @@ impl EguiPainter {
                 }),
                 targets: &[Some(ColorTargetState {
                     format: pipeline_surface_format,
-                    blend: Some(EGUI_PIPELINE_BLEND_STATE),
+                    blend: Some(options.blend),
                     write_mask: ColorWrites::ALL,
                 })],
                 compilation_options: PipelineCompilationOptions {
@@ -208,29 +300,38 @@ impl EguiPainter {
             cache: None,
         })
     }
+    /// same as [`Self::with_options`] with the default options
     pub fn new(dev: &Device, surface_format: TextureFormat) -> Self {
+        Self::with_options(dev, surface_format, EguiPainterOptions::default())
+    }
+    /// creates the painter with an egui pipeline configured by `options` (msaa sample count, blend state etc..)
+    pub fn with_options(
+        dev: &Device,
+        surface_format: TextureFormat,
+        options: EguiPainterOptions,
+    ) -> Self {
         // create uniform buffer for screen size
         let screen_size_buffer = dev.create_buffer(&BufferDescriptor {
-            label: Some("screen size uniform buffer"),
-            size: 16,
+            label: Some(&options.label("screen size uniform buffer")),
+            size: SCREEN_SIZE_UNIFORM_SIZE,
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
         // create temporary layout to create screensize uniform buffer bindgroup
         let screen_size_bindgroup_layout =
             dev.create_bind_group_layout(&BindGroupLayoutDescriptor {
-                label: Some("egui screen size bindgroup layout"),
+                label: Some(&options.label("egui screen size bindgroup layout")),
                 entries: &SCREEN_SIZE_UNIFORM_BUFFER_BINDGROUP_ENTRY,
             });
         // create texture bindgroup layout. all egui textures need to have a bindgroup with this layout to use
         // them in egui draw calls.
         let texture_bindgroup_layout = dev.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("egui texture bind group layout"),
+            label: Some(&options.label("egui texture bind group layout")),
             entries: &TEXTURE_BINDGROUP_ENTRIES,
         });
         // create screen size bind group with the above layout. store this permanently to bind before drawing egui.
         let screen_size_bind_group = dev.create_bind_group(&BindGroupDescriptor {
-            label: Some("egui bindgroup"),
+            label: Some(&options.label("egui bindgroup")),
             layout: &screen_size_bindgroup_layout,
             entries: &[BindGroupEntry {
                 binding: 0,
@@ -247,27 +348,31 @@ impl EguiPainter {
             surface_format,
             &screen_size_bindgroup_layout,
             &texture_bindgroup_layout,
+            &options,
         );
 
         // linear and nearest samplers for egui textures to use for creation of their bindgroups
         let linear_sampler = dev.create_sampler(&SamplerDescriptor {
-            label: Some("linear sampler"),
+            label: Some(&options.label("linear sampler")),
             mag_filter: FilterMode::Linear,
             min_filter: FilterMode::Linear,
-            address_mode_u: AddressMode::Repeat,
-            address_mode_v: AddressMode::Repeat,
-            address_mode_w: AddressMode::Repeat,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
             ..Default::default()
         });
         let nearest_sampler = dev.create_sampler(&SamplerDescriptor {
-            label: Some("nearest sampler"),
+            label: Some(&options.label("nearest sampler")),
             mag_filter: FilterMode::Nearest,
             min_filter: FilterMode::Nearest,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
             ..Default::default()
         });
 
         let font_sampler = dev.create_sampler(&SamplerDescriptor {
-            label: Some("egui font sampler"),
+            label: Some(&options.label("egui font sampler")),
             mag_filter: FilterMode::Linear,
             min_filter: FilterMode::Linear,
             address_mode_u: AddressMode::ClampToEdge,
@@ -276,27 +381,27 @@ impl EguiPainter {
         });
         // empty vertex and index buffers.
         let vb = dev.create_buffer(&BufferDescriptor {
-            label: Some("egui vertex buffer"),
+            label: Some(&options.label("egui vertex buffer")),
             size: 0,
             usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
         let ib = dev.create_buffer(&BufferDescriptor {
-            label: Some("egui index buffer"),
+            label: Some(&options.label("egui index buffer")),
             size: 0,
             usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
         let mipmap_shader = dev.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Blit Shader for Mipmaps"),
+            label: Some(&options.label("Blit Shader for Mipmaps")),
             source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(
                 "../blit.wgsl"
             ))),
         });
 
         let mipmap_pipeline = dev.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("blit"),
+            label: Some(&options.label("blit")),
             layout: None,
             vertex: wgpu::VertexState {
                 module: &mipmap_shader,
@@ -327,7 +432,7 @@ impl EguiPainter {
         });
 
         let mipmap_bgl = dev.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("mipmap bgl"),
+            label: Some(&options.label("mipmap bgl")),
             entries: &[
                 BindGroupLayoutEntry {
                     binding: 0,
@@ -349,7 +454,7 @@ impl EguiPainter {
         });
 
         let mipmap_sampler = dev.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("mipmap sampler"),
+            label: Some(&options.label("mipmap sampler")),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
@@ -361,10 +466,13 @@ impl EguiPainter {
         Self {
             screen_size_buffer,
             pipeline,
+            options,
             linear_sampler,
             nearest_sampler,
             managed_textures: Default::default(),
             user_textures: Default::default(),
+            user_texture_array: None,
+            custom_samplers: Default::default(),
             vb,
             ib,
             screen_size_bind_group,
@@ -379,8 +487,11 @@ impl EguiPainter {
             mipmap_bgl,
             mipmap_sampler,
             font_sampler,
+            blit_pipelines: Default::default(),
         }
     }
+    /// `surface_format` must be the format of the texture views that egui will render into.
+    /// if it changed, we recreate the pipeline with the correct fragment shader entry point for that format.
     pub fn on_resume(&mut self, dev: &Device, surface_format: TextureFormat) {
         if self.surface_format != surface_format {
             self.pipeline = Self::create_render_pipeline(
@@ -388,9 +499,285 @@ impl EguiPainter {
                 surface_format,
                 &self.screen_size_bindgroup_layout,
                 &self.texture_bindgroup_layout,
+                &self.options,
             );
+            if let Some(array) = self.user_texture_array.as_mut() {
+                array.pipeline = UserTextureArray::create_pipeline(
+                    dev,
+                    surface_format,
+                    &self.screen_size_bindgroup_layout,
+                    &array.bindgroup_layout,
+                    &self.options,
+                );
+            }
+            self.surface_format = surface_format;
         }
     }
+    /// puts the existing user textures with `ids` (created with [`Self::create_user_texture`] or inserted into [`Self::user_textures`])
+    /// into a single texture binding array, which replaces the previous array (if any).
+    /// Meshes using these textures are then drawn without switching bindgroups. see [`UserTextureArray`]
+    ///
+    /// all the textures are sampled with a sampler created from `options`.
+    /// The device must have [`crate::TEXTURE_ARRAY_FEATURES`] enabled and `max_push_constant_size` of at least 4.
+    /// Otherwise (or if any id doesn't exist), this returns false and the textures are drawn with their own bindgroups as usual.
+    ///
+    /// If you create a user texture again with one of these ids, the array is dropped, as it would still use the old texture.
+    pub fn create_user_texture_array(
+        &mut self,
+        dev: &Device,
+        ids: &[u64],
+        options: TextureOptions,
+    ) -> bool {
+        if ids.is_empty() {
+            self.user_texture_array = None;
+            return false;
+        }
+        if !UserTextureArray::is_supported(dev, ids.len()) {
+            tracing::warn!(
+                len = ids.len(),
+                "texture binding arrays are not supported by the device. falling back to per texture bindgroups"
+            );
+            return false;
+        }
+        let mut textures = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let Some(tex) = self.user_textures.get(&id) else {
+                tracing::error!(id, "cannot find user texture for texture array");
+                return false;
+            };
+            textures.push((
+                id,
+                tex.texture.create_view(&TextureViewDescriptor::default()),
+            ));
+        }
+        tracing::debug!(len = ids.len(), "creating user texture array");
+        self.user_texture_array = Some(UserTextureArray::new(
+            dev,
+            textures,
+            options,
+            self.surface_format,
+            &self.screen_size_bindgroup_layout,
+            &self.options,
+        ));
+        true
+    }
+    /// drops the texture array if it contains the user texture `id`, as the array would keep drawing the old texture.
+    fn invalidate_user_texture_array(&mut self, id: u64) {
+        if self
+            .user_texture_array
+            .as_ref()
+            .is_some_and(|array| array.indices.contains_key(&id))
+        {
+            tracing::warn!(
+                id,
+                "user texture in the texture array was replaced. dropping the texture array"
+            );
+            self.user_texture_array = None;
+        }
+    }
+    /// Drops all the gpu resources (textures, buffers, samplers, pipelines) and creates the base objects again with `dev`.
+    /// Call this after the device is lost and recreated (eg: external monitor unplugged),
+    /// as the old textures/buffers reference dead gpu objects.
+    ///
+    /// egui only uploads the font texture once. So, after this, make egui send the full font texture again
+    /// (eg: by calling [`egui::Context::set_fonts`]), so that the next frame can rebuild everything from egui's deltas.
+    /// user textures must be created again too. [`Self::custom_data`] is kept as it is, but any gpu objects inside it need to be recreated by you.
+    /// [`Self::options`] are kept too.
+    pub fn reset(&mut self, dev: &Device, surface_format: TextureFormat) {
+        tracing::warn!("resetting egui wgpu painter");
+        let custom_data = std::mem::take(&mut self.custom_data);
+        *self = Self::with_options(dev, surface_format, self.options.clone());
+        self.custom_data = custom_data;
+    }
+    /// returns the pipeline which draws a fullscreen triangle sampling a texture (bound with [`Self::mipmap_bgl`]) into a `format` target.
+    /// The pipeline is created (and cached in [`Self::blit_pipelines`]) on first use with that format.
+    pub fn blit_pipeline(&mut self, dev: &Device, format: TextureFormat) -> &RenderPipeline {
+        let bgl = &self.mipmap_bgl;
+        let options = &self.options;
+        self.blit_pipelines.entry(format).or_insert_with(|| {
+            let layout = dev.create_pipeline_layout(&PipelineLayoutDescriptor {
+                label: Some(&options.label("blit pipeline layout")),
+                bind_group_layouts: &[bgl],
+                push_constant_ranges: &[],
+            });
+            let shader = dev.create_shader_module(ShaderModuleDescriptor {
+                label: Some(&options.label("blit shader")),
+                source: ShaderSource::Wgsl(include_str!("../blit.wgsl").into()),
+            });
+            dev.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some(&options.label("blit pipeline")),
+                layout: Some(&layout),
+                vertex: VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(format.into())],
+                    compilation_options: Default::default(),
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        })
+    }
+    /// whether the default linear/nearest samplers can't be used for these options
+    fn needs_custom_sampler(options: TextureOptions) -> bool {
+        options.wrap_mode != TextureWrapMode::ClampToEdge
+            || options.magnification != options.minification
+    }
+    /// creates (if necessary) the sampler for textures which can't use the default linear/nearest samplers.
+    fn ensure_custom_sampler(&mut self, dev: &Device, options: TextureOptions) {
+        if Self::needs_custom_sampler(options) {
+            self.custom_samplers.entry(options).or_insert_with(|| {
+                create_sampler_from_texture_options(dev, options, &self.options)
+            });
+        }
+    }
+    /// sampler to use for a (non-font) texture with these options.
+    /// panics if the options need a custom sampler and [`Self::ensure_custom_sampler`] wasn't called before.
+    fn sampler_for_options(&self, options: TextureOptions) -> &Sampler {
+        if Self::needs_custom_sampler(options) {
+            return self
+                .custom_samplers
+                .get(&options)
+                .expect("custom sampler not created yet");
+        }
+        match options.magnification {
+            TextureFilter::Nearest => &self.nearest_sampler,
+            TextureFilter::Linear => &self.linear_sampler,
+        }
+    }
+    /// Creates a texture (without mipmaps) from the pixels and inserts it into [`Self::user_textures`] with `id`.
+    /// You can then use `TextureId::User(id)` with egui images to draw it.
+    ///
+    /// `format` must be a 4 bytes per pixel color format. Use `Rgba8UnormSrgb` for srgb encoded images (most images),
+    /// or `Rgba8Unorm` if the pixels are already linear (eg: a linear rendering pipeline or data textures), so that gamma is not applied twice.
+    /// egui's own managed textures (fonts etc..) are always `Rgba8UnormSrgb`.
+    ///
+    /// `size` is [width, height] in pixels and `bytes` must be tightly packed rows of 4 bytes per pixel.
+    /// The texture is created with `TEXTURE_BINDING | COPY_DST` usage, so you can update it with [`Self::update_user_texture`].
+    /// If a user texture with `id` already exists, it will be replaced.
+    pub fn create_user_texture(
+        &mut self,
+        dev: &Device,
+        queue: &Queue,
+        id: u64,
+        bytes: &[u8],
+        size: [u32; 2],
+        options: TextureOptions,
+        format: TextureFormat,
+    ) {
+        debug_assert_eq!(
+            format.block_copy_size(None),
+            Some(4),
+            "user texture format must be 4 bytes per pixel"
+        );
+        let size = Extent3d {
+            width: size[0],
+            height: size[1],
+            depth_or_array_layers: 1,
+        };
+        let texture = dev.create_texture(&TextureDescriptor {
+            label: Some(&self.options.label("egui user texture")),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[format],
+        });
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: Origin3d::default(),
+                aspect: TextureAspect::All,
+            },
+            bytes,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(size.width * 4),
+                rows_per_image: None,
+            },
+            size,
+        );
+        let view = texture.create_view(&TextureViewDescriptor {
+            label: Some(&self.options.label("egui user texture view")),
+            format: Some(format),
+            ..Default::default()
+        });
+        self.ensure_custom_sampler(dev, options);
+        let bindgroup = dev.create_bind_group(&BindGroupDescriptor {
+            label: Some(&self.options.label("egui user texture bindgroup")),
+            layout: &self.texture_bindgroup_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(self.sampler_for_options(options)),
+                },
+            ],
+        });
+        self.invalidate_user_texture_array(id);
+        self.user_textures.insert(
+            id,
+            EguiTexture {
+                texture,
+                view,
+                bindgroup,
+            },
+        );
+    }
+    /// Writes `bytes` into the existing user texture with `id`, without recreating the texture or its bindgroup.
+    /// This is meant for textures which change every frame, like a video or screen capture feed.
+    ///
+    /// The texture must have been created with `COPY_DST` usage and a format of 4 bytes per pixel (eg: `Rgba8UnormSrgb` or `Rgba8Unorm` like [`Self::create_user_texture`]).
+    /// `size` is [width, height] in pixels and must match the size of the texture. `bytes` must be tightly packed rows.
+    /// mipmaps of the texture (if any) are not regenerated.
+    ///
+    /// returns false if there's no user texture with `id`.
+    pub fn update_user_texture(
+        &self,
+        queue: &Queue,
+        id: u64,
+        bytes: &[u8],
+        size: [u32; 2],
+    ) -> bool {
+        let Some(tex) = self.user_textures.get(&id) else {
+            return false;
+        };
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &tex.texture,
+                mip_level: 0,
+                origin: Origin3d::default(),
+                aspect: TextureAspect::All,
+            },
+            bytes,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(size[0] * 4),
+                rows_per_image: None,
+            },
+            Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: 1,
+            },
+        );
+        true
+    }
     fn set_textures(
         &mut self,
         dev: &Device,
@@ -458,7 +845,7 @@ impl EguiPainter {
                 }
             } else {
                 let new_texture = dev.create_texture(&TextureDescriptor {
-                    label: None,
+                    label: Some(&self.options.label("egui managed texture")),
                     size,
                     mip_level_count,
                     sample_count: 1,
@@ -486,7 +873,7 @@ impl EguiPainter {
                     size,
                 );
                 let view = new_texture.create_view(&TextureViewDescriptor {
-                    label: None,
+                    label: Some(&self.options.label("egui managed texture view")),
                     format: Some(TextureFormat::Rgba8UnormSrgb),
                     dimension: Some(TextureViewDimension::D2),
                     aspect: TextureAspect::All,
@@ -495,9 +882,11 @@ impl EguiPainter {
                     base_array_layer: 0,
                     array_layer_count: None,
                 });
-                assert!(delta.options.magnification == delta.options.minification);
+                if !is_this_font_texure {
+                    self.ensure_custom_sampler(dev, delta.options);
+                }
                 let bindgroup = dev.create_bind_group(&BindGroupDescriptor {
-                    label: None,
+                    label: Some(&self.options.label("egui managed texture bindgroup")),
                     layout: &self.texture_bindgroup_layout,
                     entries: &[
                         BindGroupEntry {
@@ -509,10 +898,7 @@ impl EguiPainter {
                             resource: BindingResource::Sampler(if is_this_font_texure {
                                 &self.font_sampler
                             } else {
-                                match delta.options.magnification {
-                                    TextureFilter::Nearest => &self.nearest_sampler,
-                                    TextureFilter::Linear => &self.linear_sampler,
-                                }
+                                self.sampler_for_options(delta.options)
                             }),
                         },
                     ],
@@ -541,7 +927,7 @@ impl EguiPainter {
                 let views = (0..mipmap_level_count)
                     .map(|mip| {
                         texture.texture.create_view(&wgpu::TextureViewDescriptor {
-                            label: Some("mip"),
+                            label: Some(&self.options.label("mip")),
                             format: None,
                             dimension: None,
                             aspect: wgpu::TextureAspect::All,
@@ -568,11 +954,11 @@ impl EguiPainter {
                                 resource: wgpu::BindingResource::Sampler(&self.mipmap_sampler),
                             },
                         ],
-                        label: Some("mipmap bindgroup"),
+                        label: Some(&self.options.label("mipmap bindgroup")),
                     });
 
                     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                        label: None,
+                        label: Some(&self.options.label("mipmap pass")),
                         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                             view: &views[target_mip],
                             resolve_target: None,
@@ -591,6 +977,17 @@ impl EguiPainter {
             }
         }
     }
+    /// creates/updates the textures of `textures_delta` and submits the upload immediately, without drawing anything.
+    /// The freed textures are deleted along with the ones freed in the next [`Self::upload_egui_data`] call.
+    /// useful to upload textures ahead of time (eg: the font atlas at startup), so that the first frame doesn't hitch.
+    pub fn upload_textures(&mut self, dev: &Device, queue: &Queue, textures_delta: TexturesDelta) {
+        self.delete_textures.extend(textures_delta.free);
+        let mut encoder = dev.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some(&self.options.label("egui texture upload encoder")),
+        });
+        self.set_textures(dev, queue, &mut encoder, textures_delta.set);
+        queue.submit(std::iter::once(encoder.finish()));
+    }
     #[allow(clippy::too_many_arguments)]
     pub fn upload_egui_data(
         &mut self,
@@ -618,6 +1015,7 @@ impl EguiPainter {
                     }
                     TextureId::User(key) => {
                         self.user_textures.remove(&key);
+                        self.invalidate_user_texture_array(key);
                     }
                 }
             }
@@ -625,10 +1023,12 @@ impl EguiPainter {
             self.set_textures(dev, queue, encoder, textures_delta.set);
         }
         // update screen size uniform buffer
+        // must match the layout of the `ScreenSize` uniform struct in egui.wgsl
+        let [width, height] = logical_screen_size;
         queue.write_buffer(
             &self.screen_size_buffer,
             0,
-            bytemuck::cast_slice(&logical_screen_size),
+            bytemuck::cast_slice(&[width, height, 0.0f32, 0.0]),
         );
 
         {
@@ -650,7 +1050,7 @@ impl EguiPainter {
                                 .downcast_ref::<CallbackFn>()
                                 .expect("failed to downcast egui callback fn")
                                 .prepare)(
-                                dev, queue, &mut self.custom_data
+                                dev, queue, encoder, &mut self.custom_data
                             );
                             crate::scissor_from_clip_rect(
                                 &p.clip_rect,
@@ -678,7 +1078,7 @@ impl EguiPainter {
             // resize if vertex or index buffer capcities are not enough
             if self.vb_len < vb_len {
                 self.vb = dev.create_buffer(&BufferDescriptor {
-                    label: Some("egui vertex buffer"),
+                    label: Some(&self.options.label("egui vertex buffer")),
                     size: vb_len as u64 * 20,
                     usage: BufferUsages::COPY_DST | BufferUsages::VERTEX,
                     mapped_at_creation: false,
@@ -687,7 +1087,7 @@ impl EguiPainter {
             }
             if self.ib_len < ib_len {
                 self.ib = dev.create_buffer(&BufferDescriptor {
-                    label: Some("egui index buffer"),
+                    label: Some(&self.options.label("egui index buffer")),
                     size: ib_len as u64 * 4,
                     usage: BufferUsages::COPY_DST | BufferUsages::INDEX,
                     mapped_at_creation: false,
@@ -775,7 +1175,9 @@ impl EguiPainter {
                         (cb.callback
                             .downcast_ref::<CallbackFn>()
                             .expect("failed to downcast egui callback fn")
-                            .prepare)(dev, queue, &mut self.custom_data);
+                            .prepare)(
+                            dev, queue, encoder, &mut self.custom_data
+                        );
                         draw_calls.push(EguiDrawCalls::Callback {
                             clip_rect,
                             paint_callback: cb,
@@ -797,6 +1199,38 @@ impl EguiPainter {
     }
 }
 
+/// creates a sampler with filtering and address modes matching the egui [`TextureOptions`]
+/// `painter_options` is used for the label of the sampler. see [`EguiPainterOptions::label_prefix`]
+pub fn create_sampler_from_texture_options(
+    dev: &Device,
+    options: TextureOptions,
+    painter_options: &EguiPainterOptions,
+) -> Sampler {
+    let filter_mode = |filter: TextureFilter| match filter {
+        TextureFilter::Nearest => FilterMode::Nearest,
+        TextureFilter::Linear => FilterMode::Linear,
+    };
+    let address_mode = match options.wrap_mode {
+        TextureWrapMode::ClampToEdge => AddressMode::ClampToEdge,
+        TextureWrapMode::Repeat => AddressMode::Repeat,
+        TextureWrapMode::MirroredRepeat => AddressMode::MirrorRepeat,
+    };
+    dev.create_sampler(&SamplerDescriptor {
+        label: Some(&painter_options.label("egui texture options sampler")),
+        mag_filter: filter_mode(options.magnification),
+        min_filter: filter_mode(options.minification),
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        ..Default::default()
+    })
+}
+
+/// size in bytes of the screen size uniform (`ScreenSize` struct in egui.wgsl).
+/// The shader only needs the logical screen size (`vec2<f32>`, 8 bytes), but we pad it to 16 bytes,
+/// as uniform buffers are usually 16 byte aligned (and webgl requires it). The buffer, the bindgroup layout's `min_binding_size`
+/// and the shader struct must all agree on this size. Otherwise, pipeline/bindgroup creation fails validation.
+pub const SCREEN_SIZE_UNIFORM_SIZE: u64 = 16;
 pub const SCREEN_SIZE_UNIFORM_BUFFER_BINDGROUP_ENTRY: [BindGroupLayoutEntry; 1] =
     [BindGroupLayoutEntry {
         binding: 0,
@@ -804,7 +1238,7 @@ pub const SCREEN_SIZE_UNIFORM_BUFFER_BINDGROUP_ENTRY: [BindGroupLayoutEntry; 1]
         ty: BindingType::Buffer {
             ty: BufferBindingType::Uniform,
             has_dynamic_offset: false,
-            min_binding_size: NonZeroU64::new(16),
+            min_binding_size: NonZeroU64::new(SCREEN_SIZE_UNIFORM_SIZE),
         },
         count: None,
     }];
@@ -844,7 +1278,9 @@ pub const VERTEX_BUFFER_LAYOUT: [VertexBufferLayout; 1] = [VertexBufferLayout {
             offset: 8,
             shader_location: 1,
         },
-        // color as rgba (unsigned bytes which will be turned into floats inside shader)
+        // color as premultiplied srgba (unsigned bytes which will be turned into floats inside shader)
+        // egui vertex colors are in gamma space. the shader does all its math in gamma space too,
+        // and only converts to linear when writing to a srgb framebuffer (see `fs_main_linear_output`).
         VertexAttribute {
             format: VertexFormat::Unorm8x4,
             offset: 16,
@@ -880,3 +1316,159 @@ pub struct EguiTexture {
     pub view: TextureView,
     pub bindgroup: BindGroup,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_pixel_eq, render_meshes, test_device, white_font_texture};
+
+    #[test]
+    #[ignore = "needs a gpu adapter"]
+    fn user_texture_formats_gamma() {
+        let (dev, queue) = test_device();
+        let gray = Color32::from_gray(128);
+        // linear pixels shown on a srgb display
+        let gray_from_linear = ecolor::gamma_u8_from_linear_f32(128.0 / 255.0);
+        for (id, format, expected) in [
+            (1, TextureFormat::Rgba8UnormSrgb, gray.to_array()),
+            (
+                2,
+                TextureFormat::Rgba8Unorm,
+                [gray_from_linear, gray_from_linear, gray_from_linear, 255],
+            ),
+        ] {
+            // the framebuffer is not srgb, so we read back exactly what the shader outputs (srgb)
+            let mut painter = EguiPainter::new(&dev, TextureFormat::Rgba8Unorm);
+            painter.create_user_texture(
+                &dev,
+                &queue,
+                id,
+                &gray.to_array(),
+                [1, 1],
+                TextureOptions::NEAREST,
+                format,
+            );
+            let rect = Rect::from_min_size(Default::default(), vec2(4.0, 4.0));
+            let mut mesh = Mesh::with_texture(TextureId::User(id));
+            mesh.add_rect_with_uv(
+                rect,
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+                Color32::WHITE,
+            );
+            let pixels = render_meshes(
+                &dev,
+                &queue,
+                &mut painter,
+                TextureFormat::Rgba8Unorm,
+                4,
+                vec![ClippedPrimitive {
+                    clip_rect: rect,
+                    primitive: Primitive::Mesh(mesh),
+                }],
+                white_font_texture(),
+            );
+            assert_pixel_eq(pixels[5], expected);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter"]
+    fn linear_sampler_clamps_to_edge() {
+        let (dev, queue) = test_device();
+        let left = Color32::from_rgb(255, 0, 0);
+        let right = Color32::from_rgb(0, 0, 255);
+        let mut textures_delta = white_font_texture();
+        textures_delta.set.push((
+            TextureId::Managed(1),
+            ImageDelta::full(
+                ColorImage {
+                    size: [2, 1],
+                    pixels: vec![left, right],
+                },
+                TextureOptions::LINEAR,
+            ),
+        ));
+        let mut painter = EguiPainter::new(&dev, TextureFormat::Rgba8Unorm);
+        let rect = Rect::from_min_size(Default::default(), vec2(4.0, 4.0));
+        let mut mesh = Mesh::with_texture(TextureId::Managed(1));
+        // uvs slightly outside 0..1, like egui's rounding does at the edges of images.
+        // with a repeating sampler, the edge pixels would blend in the opposite edge texel.
+        mesh.add_rect_with_uv(
+            rect,
+            Rect::from_min_max(pos2(-0.02, 0.0), pos2(1.02, 1.0)),
+            Color32::WHITE,
+        );
+        let pixels = render_meshes(
+            &dev,
+            &queue,
+            &mut painter,
+            TextureFormat::Rgba8Unorm,
+            4,
+            vec![ClippedPrimitive {
+                clip_rect: rect,
+                primitive: Primitive::Mesh(mesh),
+            }],
+            textures_delta,
+        );
+        // second row, first and last pixel
+        assert_pixel_eq(pixels[4], left.to_array());
+        assert_pixel_eq(pixels[7], right.to_array());
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter"]
+    fn screen_size_uniform_matches_shader() {
+        let (dev, _queue) = test_device();
+        // creating the painter creates the screen size buffer, its bindgroup and the pipeline (validated against the shader).
+        dev.push_error_scope(ErrorFilter::Validation);
+        let painter = EguiPainter::new(&dev, TextureFormat::Rgba8UnormSrgb);
+        let error = pollster::block_on(dev.pop_error_scope());
+        assert!(error.is_none(), "validation error: {error:?}");
+        assert_eq!(painter.screen_size_buffer.size(), SCREEN_SIZE_UNIFORM_SIZE);
+    }
+
+    #[test]
+    fn different_min_mag_filters() {
+        let options = TextureOptions {
+            magnification: TextureFilter::Linear,
+            minification: TextureFilter::Nearest,
+            ..TextureOptions::LINEAR
+        };
+        assert!(EguiPainter::needs_custom_sampler(options));
+        assert!(!EguiPainter::needs_custom_sampler(TextureOptions::LINEAR));
+        assert!(!EguiPainter::needs_custom_sampler(TextureOptions::NEAREST));
+    }
+
+    #[test]
+    #[ignore = "needs a gpu adapter"]
+    fn different_min_mag_filters_sampler() {
+        let options = TextureOptions {
+            magnification: TextureFilter::Linear,
+            minification: TextureFilter::Nearest,
+            ..TextureOptions::LINEAR
+        };
+        let (dev, queue) = test_device();
+        let mut painter = EguiPainter::new(&dev, TextureFormat::Rgba8UnormSrgb);
+        painter.upload_textures(
+            &dev,
+            &queue,
+            TexturesDelta {
+                set: vec![(
+                    TextureId::Managed(1),
+                    ImageDelta::full(ColorImage::new([4, 4], Color32::WHITE), options),
+                )],
+                free: vec![],
+            },
+        );
+        assert!(painter.managed_textures.contains_key(&1));
+        let sampler = painter
+            .custom_samplers
+            .get(&options)
+            .expect("custom sampler must be created for different min/mag filters");
+        assert!(std::ptr::eq(painter.sampler_for_options(options), sampler));
+        assert!(std::ptr::eq(
+            painter.sampler_for_options(TextureOptions::NEAREST),
+            &painter.nearest_sampler
+        ));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::EguiPainter;
+use egui::{epaint::Vertex, Color32, Pos2};
+use wgpu::*;
+
+/// Offscreen render target used when egui is supersampled. see [`crate::WgpuBackend::supersample_factor`]
+///
+/// egui is rendered into this texture at a higher resolution, and then drawn onto the surface as a single textured quad
+/// using the egui pipeline. The linear sampler does the downsampling.
+pub struct SupersampleTarget {
+    /// created with the srgb variant of [`Self::format`], so that sampling always decodes to linear like other egui textures.
+    pub texture: Texture,
+    /// egui renders into this view, which uses [`Self::format`]
+    pub view: TextureView,
+    /// srgb view of the texture, which is bound when compositing
+    pub sample_view: TextureView,
+    /// format of the surface (and the egui pipeline)
+    pub format: TextureFormat,
+    /// size of the texture in physical pixels
+    pub size: [u32; 2],
+    /// bindgroup of the texture with egui's texture bindgroup layout and linear sampler
+    pub bindgroup: BindGroup,
+    /// 4 vertices of the fullscreen quad. updated every frame, as logical screen size might change
+    pub vb: Buffer,
+    /// 6 indices of the fullscreen quad
+    pub ib: Buffer,
+}
+
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 2, 1, 3];
+
+impl SupersampleTarget {
+    /// `format` must be the surface format, as we reuse the egui pipeline to render into this texture.
+    pub fn new(dev: &Device, painter: &EguiPainter, format: TextureFormat, size: [u32; 2]) -> Self {
+        let texture = dev.create_texture(&TextureDescriptor {
+            label: Some(&painter.options.label("egui supersample texture")),
+            size: Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            // for non-srgb surfaces, egui shader writes gamma encoded colors. So, we need the srgb view to decode them when sampling.
+            format: format.add_srgb_suffix(),
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[format],
+        });
+        let view = texture.create_view(&TextureViewDescriptor {
+            label: Some(&painter.options.label("egui supersample render view")),
+            format: Some(format),
+            ..Default::default()
+        });
+        let sample_view = texture.create_view(&Default::default());
+        let bindgroup = dev.create_bind_group(&BindGroupDescriptor {
+            label: Some(&painter.options.label("egui supersample bindgroup")),
+            layout: &painter.texture_bindgroup_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&sample_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&painter.linear_sampler),
+                },
+            ],
+        });
+        let vb = dev.create_buffer(&BufferDescriptor {
+            label: Some(&painter.options.label("egui supersample quad vertex buffer")),
+            size: std::mem::size_of::<[Vertex; 4]>() as u64,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let ib = dev.create_buffer(&BufferDescriptor {
+            label: Some(&painter.options.label("egui supersample quad index buffer")),
+            size: std::mem::size_of::<[u32; 6]>() as u64,
+            usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+            mapped_at_creation: true,
+        });
+        ib.slice(..)
+            .get_mapped_range_mut()
+            .copy_from_slice(bytemuck::cast_slice(&QUAD_INDICES));
+        ib.unmap();
+        Self {
+            texture,
+            view,
+            sample_view,
+            format,
+            size,
+            bindgroup,
+            vb,
+            ib,
+        }
+    }
+    /// writes the quad covering the whole screen into the vertex buffer
+    pub fn update_quad(&self, queue: &Queue, logical_screen_size: [f32; 2]) {
+        let [width, height] = logical_screen_size;
+        let vertex = |x: f32, y: f32| Vertex {
+            pos: Pos2::new(x * width, y * height),
+            uv: Pos2::new(x, y),
+            color: Color32::WHITE,
+        };
+        let vertices = [
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(0.0, 1.0),
+            vertex(1.0, 1.0),
+        ];
+        queue.write_buffer(&self.vb, 0, bytemuck::cast_slice(&vertices));
+    }
+    /// draws the supersampled egui texture onto the render pass target (usually the surface).
+    /// uses the screen size uniform of the painter, so this must be recorded in the same frame as egui's draw calls.
+    pub fn composite<'rpass>(
+        &'rpass self,
+        painter: &'rpass EguiPainter,
+        rpass: &mut RenderPass<'rpass>,
+    ) {
+        rpass.set_pipeline(&painter.pipeline);
+        rpass.set_bind_group(0, &painter.screen_size_bind_group, &[]);
+        rpass.set_bind_group(1, &self.bindgroup, &[]);
+        rpass.set_vertex_buffer(0, self.vb.slice(..));
+        rpass.set_index_buffer(self.ib.slice(..), IndexFormat::Uint32);
+        rpass.draw_indexed(0..QUAD_INDICES.len() as u32, 0, 0..1);
+    }
+}
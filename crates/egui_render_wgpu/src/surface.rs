@@ -18,6 +18,11 @@ pub struct SurfaceManager {
     /// if we don't find one, we will just use the first surface format support.
     /// so, if you don't care about the surface format, just set this to an empty vector.
     surface_formats_priority: Vec<TextureFormat>,
+    /// set when the acquired surface image is `suboptimal` (eg: after a resize on some drivers).
+    /// We keep using that image for the current frame, and reconfigure the surface with the latest size before acquiring the next one.
+    pub needs_reconfigure: bool,
+    /// prepended to the label of the surface view. see [`crate::WgpuConfig::label_prefix`]
+    pub label_prefix: String,
 }
 impl Drop for SurfaceManager {
     fn drop(&mut self) {
@@ -25,6 +30,9 @@ impl Drop for SurfaceManager {
     }
 }
 impl SurfaceManager {
+    fn label(&self, name: &str) -> String {
+        format!("{}{name}", self.label_prefix)
+    }
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         window: Option<Box<dyn WindowHandle>>,
@@ -43,6 +51,8 @@ impl SurfaceManager {
             surface,
             surface_config,
             surface_formats_priority,
+            needs_reconfigure: false,
+            label_prefix: String::new(),
         };
         surface_manager.reconfigure_surface(
             window,
@@ -60,6 +70,13 @@ impl SurfaceManager {
         device: &Device,
     ) {
         if let Some(surface) = self.surface.as_ref() {
+            if std::mem::take(&mut self.needs_reconfigure) {
+                let latest_fb_size = latest_framebuffer_size_getter();
+                debug!(?latest_fb_size, "reconfiguring suboptimal surface");
+                self.surface_config.width = latest_fb_size[0];
+                self.surface_config.height = latest_fb_size[1];
+                surface.configure(device, &self.surface_config);
+            }
             let current_surface_image = surface.get_current_texture().unwrap_or_else(|_| {
                 let latest_fb_size = latest_framebuffer_size_getter();
                 self.surface_config.width = latest_fb_size[0];
@@ -70,12 +87,16 @@ impl SurfaceManager {
                 })
             });
             if current_surface_image.suboptimal {
-                tracing::warn!("current surface image is suboptimal. ");
+                // reconfiguring now would invalidate the image we just acquired. so, we do it on the next frame.
+                tracing::warn!(
+                    "current surface image is suboptimal. reconfiguring on the next frame"
+                );
+                self.needs_reconfigure = true;
             }
             let surface_view = current_surface_image
                 .texture
                 .create_view(&TextureViewDescriptor {
-                    label: Some("surface view"),
+                    label: Some(&self.label("surface view")),
                     format: Some(self.surface_config.format),
                     dimension: Some(TextureViewDimension::D2),
                     aspect: TextureAspect::All,
@@ -0,0 +1,136 @@
+//! shared fixtures for the tests which render with a gpu.
+use crate::EguiPainter;
+use wgpu::*;
+
+/// device for tests which need a gpu. panics if there's no adapter, so those tests are `#[ignore]`d.
+/// run them with `cargo test -- --ignored` on a machine with a gpu.
+pub(crate) fn test_device() -> (Device, Queue) {
+    pollster::block_on(async {
+        let instance = Instance::new(InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&RequestAdapterOptions::default())
+            .await
+            .expect("no gpu adapter available for tests");
+        adapter
+            .request_device(
+                &DeviceDescriptor {
+                    required_limits: adapter.limits(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .expect("failed to create device for tests")
+    })
+}
+/// renders `meshes` with `painter` into a `size` x `size` texture of `format` (cleared to transparent)
+/// and reads back its pixels (row by row).
+pub(crate) fn render_meshes(
+    dev: &Device,
+    queue: &Queue,
+    painter: &mut EguiPainter,
+    format: TextureFormat,
+    size: u32,
+    meshes: Vec<egui::ClippedPrimitive>,
+    textures_delta: egui::TexturesDelta,
+) -> Vec<[u8; 4]> {
+    let extent = Extent3d {
+        width: size,
+        height: size,
+        depth_or_array_layers: 1,
+    };
+    let target = dev.create_texture(&TextureDescriptor {
+        label: Some("test render target"),
+        size: extent,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = target.create_view(&TextureViewDescriptor::default());
+    let mut encoder = dev.create_command_encoder(&CommandEncoderDescriptor::default());
+    let draw_calls = painter.upload_egui_data(
+        dev,
+        queue,
+        meshes,
+        textures_delta,
+        [size as f32; 2],
+        [size; 2],
+        &mut encoder,
+    );
+    {
+        let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("test render pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::TRANSPARENT),
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        painter.draw_egui_with_renderpass(&mut rpass, draw_calls);
+    }
+    // rows of the copy must be aligned
+    let row_size = size * 4;
+    let padded_row_size = row_size.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = dev.create_buffer(&BufferDescriptor {
+        label: Some("test readback buffer"),
+        size: (padded_row_size * size) as u64,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        target.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_size),
+                rows_per_image: None,
+            },
+        },
+        extent,
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+    let slice = buffer.slice(..);
+    slice.map_async(MapMode::Read, |result| {
+        result.expect("failed to map readback buffer")
+    });
+    dev.poll(Maintain::Wait);
+    let data = slice.get_mapped_range();
+    data.chunks(padded_row_size as usize)
+        .flat_map(|row| {
+            row[..row_size as usize]
+                .chunks(4)
+                .map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+        })
+        .collect()
+}
+/// a 1x1 white font texture, so that meshes using the default texture and uv just draw their vertex colors.
+pub(crate) fn white_font_texture() -> egui::TexturesDelta {
+    egui::TexturesDelta {
+        set: vec![(
+            egui::TextureId::Managed(0),
+            egui::epaint::ImageDelta::full(
+                egui::ColorImage::new([1, 1], egui::Color32::WHITE),
+                egui::TextureOptions::LINEAR,
+            ),
+        )],
+        free: vec![],
+    }
+}
+/// asserts that the channels of the pixels differ by at most 1 (rounding in the srgb conversions).
+pub(crate) fn assert_pixel_eq(actual: [u8; 4], expected: [u8; 4]) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, e)| a.abs_diff(*e) <= 1),
+        "expected pixel {expected:?}, got {actual:?}"
+    );
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashMap, num::NonZeroU32};
+
+use egui::TextureOptions;
+use wgpu::*;
+
+use crate::{
+    EguiPainterOptions, EGUI_PIPELINE_PRIMITIVE_STATE, EGUI_SHADER_SRC, VERTEX_BUFFER_LAYOUT,
+};
+
+pub const EGUI_ARRAY_SHADER_SRC: &str = include_str!("../egui_array.wgsl");
+/// device features required by [`UserTextureArray`]. The array index of each draw call is passed with push constants.
+pub const TEXTURE_ARRAY_FEATURES: Features =
+    Features::TEXTURE_BINDING_ARRAY.union(Features::PUSH_CONSTANTS);
+/// size of the push constant (a `u32` index) used by [`UserTextureArray::pipeline`]
+pub const TEXTURE_ARRAY_PUSH_CONSTANT_SIZE: u32 = 4;
+
+/// A batch of user textures bound as a single texture binding array. see [`crate::EguiPainter::create_user_texture_array`]
+///
+/// egui meshes using these textures are drawn with [`Self::pipeline`] and the texture is picked by an index in push constants.
+/// So, drawing lots of small user textures (sprite sheets, icon sets) doesn't need a bindgroup switch per texture.
+pub struct UserTextureArray {
+    /// user texture id -> index of the texture in the array
+    pub indices: HashMap<u64, u32>,
+    /// the views are kept alive by the bindgroup, but we keep them around for the users.
+    pub views: Vec<TextureView>,
+    pub bindgroup_layout: BindGroupLayout,
+    pub bindgroup: BindGroup,
+    /// egui pipeline which samples from the texture array
+    pub pipeline: RenderPipeline,
+}
+
+impl UserTextureArray {
+    /// whether the device supports texture arrays (features and limits).
+    /// `len` is the number of textures you want in the array.
+    pub fn is_supported(dev: &Device, len: usize) -> bool {
+        let limits = dev.limits();
+        dev.features().contains(TEXTURE_ARRAY_FEATURES)
+            && limits.max_push_constant_size >= TEXTURE_ARRAY_PUSH_CONSTANT_SIZE
+            && len <= limits.max_sampled_textures_per_shader_stage as usize
+    }
+    /// `textures` are (user texture id, view) pairs. all textures are sampled with a sampler created from `options`.
+    /// `len` of `textures` must be greater than zero.
+    pub fn new(
+        dev: &Device,
+        textures: Vec<(u64, TextureView)>,
+        options: TextureOptions,
+        surface_format: TextureFormat,
+        screen_size_bindgroup_layout: &BindGroupLayout,
+        painter_options: &EguiPainterOptions,
+    ) -> Self {
+        assert!(!textures.is_empty(), "texture array can't be empty");
+        let (ids, views): (Vec<u64>, Vec<TextureView>) = textures.into_iter().unzip();
+        let indices = ids
+            .into_iter()
+            .enumerate()
+            .map(|(index, id)| (id, index as u32))
+            .collect();
+        let bindgroup_layout = dev.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some(&painter_options.label("egui texture array bindgroup layout")),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: NonZeroU32::new(views.len() as u32),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler =
+            crate::painter::create_sampler_from_texture_options(dev, options, painter_options);
+        let view_refs: Vec<&TextureView> = views.iter().collect();
+        let bindgroup = dev.create_bind_group(&BindGroupDescriptor {
+            label: Some(&painter_options.label("egui texture array bindgroup")),
+            layout: &bindgroup_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureViewArray(&view_refs),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let pipeline = Self::create_pipeline(
+            dev,
+            surface_format,
+            screen_size_bindgroup_layout,
+            &bindgroup_layout,
+            painter_options,
+        );
+        Self {
+            indices,
+            views,
+            bindgroup_layout,
+            bindgroup,
+            pipeline,
+        }
+    }
+    /// same as [`crate::EguiPainter::create_render_pipeline`], but with the texture array bindgroup layout and push constants.
+    pub fn create_pipeline(
+        dev: &Device,
+        pipeline_surface_format: TextureFormat,
+        screen_size_bindgroup_layout: &BindGroupLayout,
+        texture_array_bindgroup_layout: &BindGroupLayout,
+        options: &EguiPainterOptions,
+    ) -> RenderPipeline {
+        let layout = dev.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some(&options.label("egui texture array pipeline layout")),
+            bind_group_layouts: &[screen_size_bindgroup_layout, texture_array_bindgroup_layout],
+            push_constant_ranges: &[PushConstantRange {
+                stages: ShaderStages::FRAGMENT,
+                range: 0..TEXTURE_ARRAY_PUSH_CONSTANT_SIZE,
+            }],
+        });
+        let shader_module = dev.create_shader_module(ShaderModuleDescriptor {
+            label: Some(&options.label("egui texture array shader src")),
+            source: ShaderSource::Wgsl(
+                format!("{EGUI_SHADER_SRC}\n{EGUI_ARRAY_SHADER_SRC}").into(),
+            ),
+        });
+        dev.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some(&options.label("egui texture array pipeline")),
+            layout: Some(&layout),
+            vertex: VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &VERTEX_BUFFER_LAYOUT,
+                compilation_options: Default::default(),
+            },
+            primitive: EGUI_PIPELINE_PRIMITIVE_STATE,
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: options.sample_count,
+                ..Default::default()
+            },
+            fragment: Some(FragmentState {
+                module: &shader_module,
+                entry_point: Some(if pipeline_surface_format.is_srgb() {
+                    "fs_array_linear_output"
+                } else {
+                    "fs_array_srgb_output"
+                }),
+                targets: &[Some(ColorTargetState {
+                    format: pipeline_surface_format,
+                    blend: Some(options.blend),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache: None,
+        })
+    }
+}
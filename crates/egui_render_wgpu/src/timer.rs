@@ -0,0 +1,148 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use wgpu::*;
+
+/// state of the readback buffer of [`GpuTimer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadbackState {
+    /// readback buffer is free to be used for the next copy
+    Idle,
+    /// we recorded a copy of the resolved timestamps into the readback buffer. but it isn't submitted yet.
+    CopyRecorded,
+    /// the copy has been submitted and we requested the buffer to be mapped.
+    Mapping,
+}
+
+/// Measures how long the egui render pass takes on the gpu using timestamp queries.
+///
+/// This is only created if the device has [`Features::TIMESTAMP_QUERY`] enabled.
+/// So, make sure to add it to the required features of the [`DeviceDescriptor`] if you want gpu timings.
+///
+/// The timestamps are read back asynchronously, so the durations lag behind by a frame or two.
+pub struct GpuTimer {
+    /// two timestamps. start and end of the egui render pass
+    pub query_set: QuerySet,
+    /// timestamps are resolved into this buffer
+    pub resolve_buffer: Buffer,
+    /// resolved timestamps are copied into this buffer, so that we can map it and read them on the cpu.
+    pub readback_buffer: Buffer,
+    /// number of nanoseconds per timestamp tick
+    pub timestamp_period: f32,
+    readback_state: ReadbackState,
+    /// set by the map callback once the readback buffer can be read
+    readback_mapped: Arc<AtomicBool>,
+    /// set by the map callback if mapping failed. The buffer isn't mapped in that case, so we just go back to idle.
+    readback_failed: Arc<AtomicBool>,
+}
+
+/// size of two u64 timestamps
+const TIMESTAMPS_SIZE: u64 = 16;
+
+impl GpuTimer {
+    /// returns None if the device doesn't support [`Features::TIMESTAMP_QUERY`]
+    /// `label_prefix` is prepended to the labels of the query set and buffers. see [`crate::WgpuConfig::label_prefix`]
+    pub fn new(device: &Device, queue: &Queue, label_prefix: &str) -> Option<Self> {
+        let label = |name: &str| format!("{label_prefix}{name}");
+        if !device.features().contains(Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let query_set = device.create_query_set(&QuerySetDescriptor {
+            label: Some(&label("egui timestamp query set")),
+            ty: QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some(&label("egui timestamp resolve buffer")),
+            size: TIMESTAMPS_SIZE,
+            usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some(&label("egui timestamp readback buffer")),
+            size: TIMESTAMPS_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            timestamp_period: queue.get_timestamp_period(),
+            readback_state: ReadbackState::Idle,
+            readback_mapped: Default::default(),
+            readback_failed: Default::default(),
+        })
+    }
+    /// use this for the `timestamp_writes` field of the render pass that you want to measure
+    pub fn timestamp_writes(&self) -> RenderPassTimestampWrites<'_> {
+        RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        }
+    }
+    /// records the commands to resolve the timestamps and copy them to the readback buffer.
+    /// This is skipped if the previous timestamps haven't been read yet.
+    pub fn resolve(&mut self, encoder: &mut CommandEncoder) {
+        if self.readback_state != ReadbackState::Idle {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            TIMESTAMPS_SIZE,
+        );
+        self.readback_state = ReadbackState::CopyRecorded;
+    }
+    /// must be called *after* submitting the command encoder which was used in [`Self::resolve`]
+    pub fn map_readback(&mut self) {
+        if self.readback_state != ReadbackState::CopyRecorded {
+            return;
+        }
+        let mapped = self.readback_mapped.clone();
+        let failed = self.readback_failed.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| match result {
+                Ok(_) => mapped.store(true, Ordering::Release),
+                Err(e) => {
+                    tracing::error!("failed to map timestamp readback buffer: {e}");
+                    failed.store(true, Ordering::Release);
+                }
+            });
+        self.readback_state = ReadbackState::Mapping;
+    }
+    /// polls the device and returns the duration of the measured render pass, if the timestamps are ready to be read.
+    pub fn try_read(&mut self, device: &Device) -> Option<Duration> {
+        if self.readback_state != ReadbackState::Mapping {
+            return None;
+        }
+        device.poll(Maintain::Poll);
+        if self.readback_failed.swap(false, Ordering::Acquire) {
+            // a failed map leaves the buffer unmapped. so, we can record the next copy right away.
+            self.readback_state = ReadbackState::Idle;
+            return None;
+        }
+        if !self.readback_mapped.swap(false, Ordering::Acquire) {
+            return None;
+        }
+        let timestamps: [u64; 2] = {
+            let view = self.readback_buffer.slice(..).get_mapped_range();
+            bytemuck::pod_read_unaligned(&view[..])
+        };
+        self.readback_buffer.unmap();
+        self.readback_state = ReadbackState::Idle;
+        let ticks = timestamps[1].saturating_sub(timestamps[0]);
+        Some(Duration::from_nanos(
+            (ticks as f64 * self.timestamp_period as f64) as u64,
+        ))
+    }
+}
@@ -10,6 +10,7 @@ use glfw::StandardCursor;
 use glfw::WindowEvent;
 use glfw::WindowHint;
 use glfw::{Action, Modifiers};
+use std::collections::HashMap;
 use tracing::info;
 /// This is the window backend for egui using [`glfw`]
 /// You can configure most of it at startup using [`GlfwConfig`].
@@ -44,21 +45,121 @@ pub struct GlfwBackend {
     pub window_position: [i32; 2],
     /// ratio between pixels and virtual units
     pub physical_pixels_per_virtual_unit: f32,
-    /// ratio between logical points and physical pixels
+    /// ratio between logical points and physical pixels.
+    /// egui only supports a uniform pixels_per_point, so this is the x axis of [`Self::content_scale`]
+    /// and all logical <-> physical conversions (cursor, window size etc..) use it for both axes. Otherwise, the ui would be stretched.
     pub scale: f32,
+    /// glfw's content scale `[x, y]`. On (rare) displays with non-square pixels, these differ.
+    /// use this if you render your own content and want to correct for the aspect ratio of pixels.
+    pub content_scale: [f32; 2],
     pub raw_input: RawInput,
     pub cursor_icon: glfw::StandardCursor,
     pub frame_events: Vec<WindowEvent>,
+    /// glfw time (see [`Self::time`]) at which each event of [`Self::frame_events`] was received (same index).
+    ///
+    /// egui events don't carry timestamps, egui uses `raw_input.time` (set in [`Self::tick`]) for click timing.
+    /// Multiple clicks within a single frame are *not* collapsed though. egui processes each press/release in order,
+    /// and as they share the frame time, the second click is detected as a double click (and the third as a triple click).
+    /// These are useful if you need the precise timing yourself (eg: rhythm or reaction time measurements).
+    pub frame_event_timestamps: Vec<f64>,
     pub resized_event_pending: bool,
+    /// set in `tick` when the OS asks us to redraw the window contents ([`WindowEvent::Refresh`]), eg: after the window is uncovered or resized.
+    /// The event loop must draw (and present) a frame immediately, even if egui doesn't want a repaint, and reset this to false.
+    ///
+    /// on windows, glfw blocks inside the OS's modal resize/move loop. As we receive events via a channel,
+    /// the refresh can only be handled after that loop returns (or when the OS lets glfw return in between).
+    pub refresh_requested: bool,
+    /// If false, the event loop keeps pumping events (and running the gui for input), but skips tessellation/render/present entirely.
+    /// The last presented frame stays on screen. see [`Self::set_rendering_enabled`]. default is true.
+    pub rendering_enabled: bool,
+    /// whether the window is shown. A window which is waiting for [`Self::show_after_first_frame`] also counts as visible.
+    /// see [`Self::set_visible`]
+    pub visible: bool,
+    /// why the window is closing. set by the `Close` event or [`Self::request_close`]. `None` if no close was requested.
+    pub close_reason: Option<CloseReason>,
+    /// the mode set by [`Self::set_window_monitor`]. `Windowed` by default.
+    pub fullscreen_mode: FullscreenMode,
+    /// position and size `[x, y, width, height]` (in virtual units) of the window before it left windowed mode.
+    /// restored when going back to [`FullscreenMode::Windowed`].
+    pub windowed_geometry: Option<[i32; 4]>,
+    /// refresh rate (in Hz) to use for [`FullscreenMode::Exclusive`] without an explicit video mode. `None` keeps the monitor's current rate.
+    /// see [`Self::set_refresh_rate_hint`]
+    pub refresh_rate_hint: Option<u32>,
     /// in logical points
     pub cursor_pos: [f32; 2],
     pub cursor_inside_bounds: bool,
+    /// modifiers of the latest key/char/mouse button event.
+    /// This also contains lock key state (CapsLock/NumLock), which egui's modifiers don't have.
+    /// see [`Self::is_caps_lock_on`] and [`Self::is_num_lock_on`]
     pub modifiers: glfw::Modifiers,
+    /// egui modifiers which are currently held down.
+    /// Updated on every key/char/mouse button event, and attached to all emitted egui events and [`RawInput::modifiers`].
+    pub current_modifiers: egui::Modifiers,
+    /// The unit of glfw's scroll offsets in the egui `MouseWheel` events. glfw doesn't tell us which device is scrolling.
+    /// 1. [`MouseWheelUnit::Point`] (default): offsets are used as logical points. good for fine grained continuous scrolling (trackpads).
+    /// 2. [`MouseWheelUnit::Line`]: every unit of offset is a line. good for discrete scrolling (mouse wheels), as each wheel "click" will scroll a line.
+    pub scroll_unit: MouseWheelUnit,
     pub title: String,
     pub focused: bool,
+    /// whether the window is minimized. updated by `Iconify` events
+    pub iconified: bool,
+    /// whether the window is maximized. updated by `Maximize` events
+    pub maximized: bool,
     /// if the window is mouse_passthrough or not.
     /// We cache this, to avoid redundant calls to [glfw::Window::set_mouse_passthrough]
     pub passthrough: bool,
+    /// the passthrough requests of the app, content regions and egui. The window's passthrough state is decided from this.
+    /// see [`PassthroughController`] and [`Self::apply_passthrough`]
+    pub passthrough_controller: PassthroughController,
+    /// If true, the window takes the keyboard focus while the cursor is over its content, even while it is mouse passthrough. see [`Self::set_keyboard_capture`]
+    pub keyboard_capture: bool,
+    /// If true, the window is hidden and should be shown after the first frame is presented.
+    /// set to false after showing the window. see [`GlfwConfig::show_after_first_frame`] and [`Self::on_frame_presented`]
+    pub show_after_first_frame: bool,
+    /// If set, egui will lay out the ui in a fixed logical canvas of this size (eg: 1280x720 points),
+    /// which is scaled uniformly to fit the window instead of reflowing the ui on resize.
+    /// The canvas is centered in the window, and the leftover space on the sides (or top/bottom) is letterboxed.
+    /// Use [`Self::egui_screen_size_logical`] as the logical screen size when rendering egui.
+    pub fixed_logical_size: Option<[f32; 2]>,
+    /// the ongoing opacity fade started by [`Self::fade_to`]. advanced in [`Self::tick`] and set to `None` when it's done.
+    pub opacity_fade: Option<OpacityFade>,
+    /// If false, we never emit [`Event::PointerGone`] when the cursor leaves the window (neither the real `CursorEnter` event, nor the passthrough simulation).
+    /// useful if you want full control of the pointer state (eg: full screen passthrough HUDs doing their own hit-testing).
+    /// default is true.
+    pub emit_pointer_gone_on_leave: bool,
+    /// custom mapping of glfw mouse buttons to egui pointer buttons (eg: treat Button4 as middle click).
+    /// buttons not in this map use the default mapping of [`glfw_to_egui_pointer_button`]. empty by default.
+    pub mouse_button_remap: HashMap<glfw::MouseButton, PointerButton>,
+    /// mouse buttons which we reported as pressed to egui, but haven't reported as released yet (with the egui button they were mapped to).
+    /// If a drag starts inside a passthrough window and the button is released over another app, we might never get the release event.
+    /// glfw's `get_mouse_button` can't help either, as it only knows the state reported to this window by events.
+    /// So, [`Self::tick`] synthesizes the release when the passthrough simulation sees the cursor leave (or come back into) the window bounds,
+    /// when the window loses focus or when we get another press of the same button. see [`simulate_passthrough_cursor`]
+    /// This way, egui doesn't get stuck thinking that the button is held. A late release event from glfw is ignored, as we already released it.
+    pub pressed_mouse_buttons: HashMap<glfw::MouseButton, PointerButton>,
+    /// If true, [`PassthroughController::region`] is decided in [`Self::tick`], based on whether the cursor is over [`Self::content_rects`].
+    /// So, clicks on the transparent parts of the window go through to the windows below, while the ui still receives them.
+    /// see [`Self::set_auto_passthrough_from_content`]
+    pub auto_passthrough: bool,
+    /// rects (in egui points) where egui drew opaque-ish content in the previous frame. see [`content_rects_from_meshes`].
+    /// This is a practical approximation of per-pixel passthrough, as glfw doesn't have a cross-platform input region/mask api.
+    pub content_rects: Vec<egui::Rect>,
+    /// platform output of the previous egui frame (copied text, cursor icon etc..).
+    /// This is not set by the glfw backend itself. The event loop (eg: `egui_overlay`) stores it after handling the output.
+    pub last_platform_output: Option<egui::PlatformOutput>,
+    /// where egui's text caret is (in logical points), while a text edit has focus. from `PlatformOutput::ime` of the previous frame.
+    /// glfw has no api to position the IME candidate window. So, we can't do anything with this ourselves,
+    /// but you can use it with platform specific apis (eg: `ImmSetCompositionWindow` on windows) via the native window handle.
+    /// This is not set by the glfw backend itself. The event loop (eg: `egui_overlay`) stores it after handling the output.
+    pub ime_cursor_rect: Option<egui::Rect>,
+    /// If set, this is called for every glfw event in [`Self::tick`], before we process it (eg: to intercept a global hotkey).
+    /// If it returns true, the event is consumed. It won't be in [`Self::frame_events`] and egui won't see it.
+    /// Be careful with consuming window state events (eg: resize, focus or close), as the backend won't notice them either.
+    pub on_raw_event: Option<RawEventHook>,
+    /// created lazily by [`Self::set_clipboard_image`].
+    /// On linux (x11), the clipboard contents are served by this, so we need to keep it alive.
+    #[cfg(all(feature = "clipboard_image", not(target_os = "emscripten")))]
+    pub clipboard: Option<arboard::Clipboard>,
     // #[cfg(feature = "wayland")]
     // pub input_region: wayland_client::protocol::wl_region::WlRegion,
     pub events_receiver: glfw::GlfwReceiver<(f64, WindowEvent)>,
@@ -70,10 +171,93 @@ impl Drop for GlfwBackend {
         tracing::warn!("dropping glfw backend");
     }
 }
+/// reconciles the systems which want to decide whether the window is passthrough, so that the window is only toggled
+/// when the final decision changes. Without this, they could toggle the window back and forth within a frame (flicker).
+///
+/// precedence: `explicit` > `region` > `egui`. The first one that is `Some` decides. If all are `None`, the window is interactive.
+/// see [`GlfwBackend::passthrough_controller`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PassthroughController {
+    /// explicit override by the app (eg: a lock/unlock hotkey or a pure visualization overlay).
+    /// set by [`GlfwBackend::set_passthrough_override`], [`GlfwBackend::set_always_passthrough`] and [`GlfwBackend::toggle_passthrough`]
+    pub explicit: Option<bool>,
+    /// decided in [`GlfwBackend::tick`] by the cursor position over [`GlfwBackend::content_rects`], while [`GlfwBackend::auto_passthrough`] is enabled.
+    pub region: Option<bool>,
+    /// requested by the gui with [`GlfwBackend::set_passthrough`]. eg: `!egui_context.wants_pointer_input()`
+    pub egui: Option<bool>,
+}
+impl PassthroughController {
+    /// the final passthrough state according to the precedence.
+    pub fn decide(&self) -> bool {
+        self.explicit.or(self.region).or(self.egui).unwrap_or(false)
+    }
+}
+/// A timed transition of the window opacity. see [`GlfwBackend::fade_to`]
+#[derive(Debug, Clone, Copy)]
+pub struct OpacityFade {
+    /// opacity at the start of the fade
+    pub start_opacity: f32,
+    pub target_opacity: f32,
+    /// glfw time (in seconds) at which the fade started
+    pub start_time: f64,
+    /// in seconds
+    pub duration: f64,
+}
+/// how the window covers a monitor. see [`GlfwBackend::set_window_monitor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FullscreenMode {
+    /// a normal window. The geometry from before going fullscreen is restored.
+    #[default]
+    Windowed,
+    /// a window covering the whole monitor, without changing the video mode.
+    /// This keeps the framebuffer transparency, so it's usually what overlays want.
+    Borderless,
+    /// exclusive fullscreen with this video mode of the monitor (including its refresh rate).
+    /// `None` uses the current video mode of the monitor, with the refresh rate of [`GlfwBackend::refresh_rate_hint`] (if set).
+    /// transparency might not work in this mode.
+    Exclusive(Option<VideoMode>),
+}
+/// A video mode of a monitor. see [`GlfwBackend::current_video_mode`] and [`GlfwBackend::available_video_modes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    /// in screen coordinates (virtual units)
+    pub width: u32,
+    pub height: u32,
+    /// in Hz
+    pub refresh_rate: u32,
+    /// red, green and blue bit depths
+    pub bit_depths: [u32; 3],
+}
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> Self {
+        Self {
+            width: mode.width,
+            height: mode.height,
+            refresh_rate: mode.refresh_rate,
+            bit_depths: [mode.red_bits, mode.green_bits, mode.blue_bits],
+        }
+    }
+}
+/// A handle to wake up the glfw event loop from any thread. see [`GlfwBackend::event_loop_waker`]
+#[derive(Debug, Clone, Copy)]
+pub struct EventLoopWaker {
+    _private: (),
+}
+impl EventLoopWaker {
+    /// posts an empty event to glfw's event queue, which makes a blocking `wait_events`/`wait_events_timeout` return immediately.
+    /// This is the safe way to wake the event loop from other threads (eg: when a background task finishes).
+    /// If glfw is already terminated, this does nothing (except glfw logging an error).
+    pub fn wake(&self) {
+        // Safety: glfwPostEmptyEvent may be called from any thread
+        unsafe { glfw::ffi::glfwPostEmptyEvent() }
+    }
+}
 /// Signature of Glfw callback function inside [`GlfwConfig`]
 pub type GlfwCallback = Box<dyn FnOnce(&mut Glfw)>;
 /// This is the signature for window callback inside new function of [`GlfwBackend`]
 pub type WindowCallback = Box<dyn FnOnce(&mut glfw::Window)>;
+/// This is the signature of [`GlfwBackend::on_raw_event`]. return true to consume the event.
+pub type RawEventHook = Box<dyn FnMut(&WindowEvent) -> bool>;
 
 /// The configuration struct for Glfw Backend creation
 pub struct GlfwConfig {
@@ -87,6 +271,23 @@ pub struct GlfwConfig {
     /// It will be opengl window on windows/linux, and non-opengl on linux.
     /// If you want to use this with wgpu/vulkan etc.. or create your own gl context using egl, set this to false
     pub opengl_window: Option<bool>,
+    /// If true, the window will be created hidden and [`GlfwBackend::show_after_first_frame`] will be set.
+    /// The event loop is expected to show the window after presenting the first frame.
+    /// This avoids flashing a blank/garbage frame at startup.
+    pub show_after_first_frame: bool,
+    /// whether the window will have decorations (borders, titlebar etc..).
+    /// set as window hint before creation, so that the window doesn't flash decorations at startup.
+    /// use [`glfw::Window::set_decorated`] to change it at runtime.
+    pub decorated: Option<bool>,
+    /// whether the window will be always on top of other windows.
+    /// set as window hint before creation. use [`glfw::Window::set_floating`] to change it at runtime.
+    pub floating: Option<bool>,
+    /// whether the window size (in [`Self::size`]) is scaled by the monitor's content scale on creation. eg: 800x600 becomes 1600x1200 on a 200% monitor.
+    /// default is `Some(true)`. `None` leaves it to glfw's default (false).
+    /// set it to false for overlays that must match an exact pixel region (eg: aligned to a game window).
+    ///
+    /// This only affects the window size. [`GlfwBackend::scale`] (and thus egui's pixels_per_point) still follows the content scale of the monitor.
+    pub scale_to_monitor: Option<bool>,
     /// This callback is called with `&mut Glfw` just before creating a window
     /// All advanced configuration can be done here. eg: opengl settings such as gl version, depth/stencil bits etc..
     pub glfw_callback: GlfwCallback,
@@ -102,6 +303,10 @@ impl Default for GlfwConfig {
             window_title: "glfw window".to_string(),
             transparent_window: None,
             opengl_window: None,
+            show_after_first_frame: false,
+            decorated: None,
+            floating: None,
+            scale_to_monitor: Some(true),
             size: [800, 600],
         }
     }
@@ -109,14 +314,33 @@ impl Default for GlfwConfig {
 
 impl GlfwBackend {
     pub fn new(config: GlfwConfig) -> Self {
-        let mut glfw_context = glfw::init(glfw::log_errors).expect("failed to create glfw context");
-        glfw_context.window_hint(WindowHint::ScaleToMonitor(true));
+        let glfw_context = glfw::init(glfw::log_errors).expect("failed to create glfw context");
+        Self::new_with_glfw(glfw_context, config, None)
+    }
+    /// creates another window whose opengl context shares objects (textures, buffers etc..) with the context of this window.
+    /// useful for multi-window overlays, which want to share the egui font atlas or user textures across windows.
+    /// The config's hints are applied on top of the default window hints, so hints of this window are not inherited.
+    /// only meaningful for opengl windows.
+    pub fn with_shared_context(&self, config: GlfwConfig) -> Self {
+        Self::new_with_glfw(self.glfw.clone(), config, Some(&self.window))
+    }
+    fn new_with_glfw(
+        mut glfw_context: Glfw,
+        config: GlfwConfig,
+        share: Option<&glfw::Window>,
+    ) -> Self {
+        // hints are global state, so reset any hints left over from previous window creation
+        glfw_context.default_window_hints();
 
         let GlfwConfig {
             window_title,
             size,
             transparent_window,
             opengl_window,
+            show_after_first_frame,
+            decorated,
+            floating,
+            scale_to_monitor,
             glfw_callback,
             window_callback,
         } = config;
@@ -131,17 +355,36 @@ impl GlfwBackend {
                 glfw_context.window_hint(WindowHint::ClientApi(ClientApiHint::NoApi));
             }
         }
+        if show_after_first_frame {
+            glfw_context.window_hint(WindowHint::Visible(false));
+        }
+        if let Some(decorated) = decorated {
+            glfw_context.window_hint(WindowHint::Decorated(decorated));
+        }
+        if let Some(floating) = floating {
+            glfw_context.window_hint(WindowHint::Floating(floating));
+        }
+        if let Some(scale_to_monitor) = scale_to_monitor {
+            glfw_context.window_hint(WindowHint::ScaleToMonitor(scale_to_monitor));
+        }
         (glfw_callback)(&mut glfw_context);
 
         // create a window
-        let (mut window, events_receiver) = glfw_context
-            .create_window(
+        let (mut window, events_receiver) = match share {
+            Some(share) => share.create_shared(
+                size[0] as _,
+                size[1] as _,
+                &window_title,
+                glfw::WindowMode::Windowed,
+            ),
+            None => glfw_context.create_window(
                 size[0] as _,
                 size[1] as _,
                 &window_title,
                 glfw::WindowMode::Windowed,
-            )
-            .expect("failed to create glfw window");
+            ),
+        }
+        .expect("failed to create glfw window");
         let api = window.get_client_api();
         if api == glfw::ffi::OPENGL_API || api == glfw::ffi::OPENGL_ES_API {
             window.make_current();
@@ -172,9 +415,9 @@ impl GlfwBackend {
             window.set_store_lock_key_mods(should_poll);
         }
         #[cfg(not(target_os = "emscripten"))]
-        let scale = window.get_content_scale().0;
+        let content_scale: [f32; 2] = window.get_content_scale().into();
         #[cfg(target_os = "emscripten")]
-        let scale = {
+        let content_scale = {
             let scale = unsafe { emscripten_get_device_pixel_ratio() } as f32;
             if scale != 1.0 {
                 let width = (800.0 * scale) as i32;
@@ -182,13 +425,39 @@ impl GlfwBackend {
                 window.set_size(width, height);
             }
             unsafe { emscripten_set_element_css_size(CANVAS_ELEMENT_NAME, 800.0, 600.0) };
-            scale
+            [scale, scale]
         };
 
+        let size_before_callback = window.get_framebuffer_size();
         (window_callback)(&mut window);
+        // the scale to monitor resize (or a resize/move in window callback) might be applied asynchronously by the window manager.
+        // So, we process the pending events to let the size settle before querying it. Otherwise, the first frame might have the wrong size.
+        // The events stay in the channel, and will be handled by the first `tick` as usual.
+        glfw_context.poll_events();
+        // window callback might have moved the window to a different monitor
+        #[cfg(not(target_os = "emscripten"))]
+        let content_scale: [f32; 2] = window.get_content_scale().into();
+        if content_scale[0] != content_scale[1] {
+            tracing::warn!(
+                ?content_scale,
+                "content scale differs per axis. egui will use the x axis scale for both"
+            );
+        }
+        let scale = content_scale[0];
 
         // collect details and keep them updated
         let (physical_width, physical_height) = window.get_framebuffer_size();
+        if size_before_callback != (physical_width, physical_height) {
+            tracing::info!(
+                requested_width = size[0],
+                requested_height = size[1],
+                before_width = size_before_callback.0,
+                before_height = size_before_callback.1,
+                after_width = physical_width,
+                after_height = physical_height,
+                "framebuffer size changed during window creation"
+            );
+        }
         let (logical_width, logical_height) = (
             physical_width as f32 / scale,
             physical_height as f32 / scale,
@@ -206,9 +475,14 @@ impl GlfwBackend {
         let position = window.get_pos();
         let window_position = [position.0, position.1];
         let focus = window.is_focused();
-        // set raw input screen rect details so that first frame
-        // will have correct size even without any resize event
+        let iconified = window.is_iconified();
+        let visible = window.is_visible() || show_after_first_frame;
+        let maximized = window.is_maximized();
+        // set raw input screen rect, time and viewport details so that first frame
+        // will have correct values even without any events
         let raw_input = RawInput {
+            viewport_id: ViewportId::ROOT,
+            time: Some(glfw_context.get_time()),
             screen_rect: Some(egui::Rect::from_points(&[
                 Default::default(),
                 [
@@ -249,10 +523,19 @@ impl GlfwBackend {
             window,
             framebuffer_size_physical: size_physical_pixels,
             scale,
+            content_scale,
             cursor_pos: [logical_cursor_position.0, logical_cursor_position.1],
             raw_input,
             frame_events: vec![],
+            frame_event_timestamps: vec![],
             resized_event_pending: true, // provide so that on first prepare frame, renderers can set their viewport sizes
+            refresh_requested: false,
+            rendering_enabled: true,
+            visible,
+            close_reason: None,
+            fullscreen_mode: FullscreenMode::Windowed,
+            windowed_geometry: None,
+            refresh_rate_hint: None,
             cursor_icon: StandardCursor::Arrow,
             cursor_inside_bounds: false,
             window_size_logical: [logical_width, logical_height],
@@ -264,15 +547,150 @@ impl GlfwBackend {
             window_position,
             title: window_title,
             focused: focus,
+            iconified,
+            maximized,
             modifiers: Modifiers::empty(),
+            current_modifiers: Default::default(),
+            scroll_unit: MouseWheelUnit::Point,
             passthrough: pass,
+            passthrough_controller: PassthroughController {
+                egui: Some(pass),
+                ..Default::default()
+            },
+            keyboard_capture: false,
+            show_after_first_frame,
+            fixed_logical_size: None,
+            opacity_fade: None,
+            emit_pointer_gone_on_leave: true,
+            mouse_button_remap: HashMap::new(),
+            last_platform_output: None,
+            ime_cursor_rect: None,
+            on_raw_event: None,
+            pressed_mouse_buttons: HashMap::new(),
+            auto_passthrough: false,
+            content_rects: vec![],
+            #[cfg(all(feature = "clipboard_image", not(target_os = "emscripten")))]
+            clipboard: None,
         }
     }
+    /// takes the files dropped onto the window since the last call (or since the last [`Self::take_raw_input`]).
+    ///
+    /// dropped files are collected in `raw_input.dropped_files` by [`Self::tick`], and are owned by whoever takes them first.
+    /// [`Self::take_raw_input`] hands them to egui (`ctx.input(|i| i.raw.dropped_files)`). So, if you handle the drops yourself
+    /// by reading the backend, take them with this *before* the raw input is taken, instead of reading `raw_input` directly.
+    /// Otherwise, the same drop would be processed twice (by you and by egui).
+    pub fn take_dropped_files(&mut self) -> Vec<egui::DroppedFile> {
+        std::mem::take(&mut self.raw_input.dropped_files)
+    }
     /// returns raw input and scale. `scale` is only Some, if it changed (or if first frame). Otherwise it just returns None.
     pub fn take_raw_input(&mut self) -> RawInput {
-        self.raw_input.take()
+        let mut raw_input = self.raw_input.take();
+        // egui points are scaled by zoom, so we need to convert our window logical points into egui points.
+        let zoom = self.fixed_size_zoom();
+        let egui_screen_size: egui::Vec2 = self.egui_screen_size_logical().into();
+        raw_input.screen_rect = Some(match self.fixed_logical_size {
+            Some(fixed_size) => {
+                let fixed_size: egui::Vec2 = fixed_size.into();
+                // center the canvas within the window
+                egui::Rect::from_min_size(
+                    ((egui_screen_size - fixed_size) / 2.0).to_pos2(),
+                    fixed_size,
+                )
+            }
+            None => egui::Rect::from_min_size(Default::default(), egui_screen_size),
+        });
+        if let Some(vp) = raw_input.viewports.get_mut(&ViewportId::ROOT) {
+            vp.native_pixels_per_point = Some(self.scale * zoom);
+        }
+        if zoom != 1.0 {
+            for event in raw_input.events.iter_mut() {
+                match event {
+                    Event::PointerMoved(pos) | Event::PointerButton { pos, .. } => {
+                        *pos = (pos.to_vec2() / zoom).to_pos2();
+                    }
+                    Event::MouseWheel {
+                        unit: MouseWheelUnit::Point,
+                        delta,
+                        ..
+                    } => {
+                        *delta /= zoom;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        raw_input
+    }
+    /// the factor by which the [`Self::fixed_logical_size`] canvas is scaled to fit the window.
+    /// 1.0 if it is `None` (or if the window has zero size, eg: when minimized)
+    pub fn fixed_size_zoom(&self) -> f32 {
+        match self.fixed_logical_size {
+            Some([width, height]) if width > 0.0 && height > 0.0 => {
+                let zoom =
+                    (self.window_size_logical[0] / width).min(self.window_size_logical[1] / height);
+                if zoom > 0.0 {
+                    zoom
+                } else {
+                    1.0
+                }
+            }
+            _ => 1.0,
+        }
+    }
+    /// size of the window in egui points. This is the logical screen size that should be used to render egui.
+    /// same as [`Self::window_size_logical`], unless [`Self::fixed_logical_size`] is set.
+    pub fn egui_screen_size_logical(&self) -> [f32; 2] {
+        let zoom = self.fixed_size_zoom();
+        [
+            self.window_size_logical[0] / zoom,
+            self.window_size_logical[1] / zoom,
+        ]
     }
 
+    /// marks the window to be closed, and records the reason. The event loop will exit after the current frame.
+    /// If a close was already requested, the first reason is kept.
+    pub fn request_close(&mut self, reason: CloseReason) {
+        if self.close_reason.is_none() {
+            tracing::info!(?reason, "window close requested");
+            self.close_reason = Some(reason);
+        }
+        self.window.set_should_close(true);
+    }
+    /// whether the window should close. see [`Self::close_reason`] for why.
+    pub fn should_close(&self) -> bool {
+        self.window.should_close()
+    }
+    /// cancels a requested close. eg: to show an "unsaved changes" confirmation dialog first.
+    /// must be called before the event loop checks [`Self::should_close`] (eg: inside your gui code).
+    pub fn cancel_close(&mut self) {
+        self.close_reason = None;
+        self.window.set_should_close(false);
+    }
+    /// seconds since glfw was initialized. This is the canonical time source of the backend.
+    /// `raw_input.time` (and thus egui's time) is set from this in [`Self::tick`], so use this for your animations too,
+    /// instead of mixing it with [`std::time::Instant`]. glfw's timer is monotonic (unless someone calls `glfw.set_time`).
+    pub fn time(&self) -> f64 {
+        self.glfw.get_time()
+    }
+    /// sets the swap interval (vsync) of the opengl context of this window. 0 = off (uncapped), 1 = vsync, n = every nth vblank,
+    /// -1 = adaptive vsync (tears instead of waiting when a frame is late. needs `WGL_EXT_swap_control_tear`/`GLX_EXT_swap_control_tear`).
+    /// Without calling this, the interval is the driver's default.
+    ///
+    /// makes the window's context current. does nothing for non-opengl windows (eg: wgpu uses present modes instead).
+    pub fn set_swap_interval(&mut self, interval: i32) {
+        if !self.is_opengl() {
+            tracing::warn!(interval, "ignoring swap interval for a non-opengl window");
+            return;
+        }
+        let swap_interval = match interval {
+            0 => glfw::SwapInterval::None,
+            i if i < 0 => glfw::SwapInterval::Adaptive,
+            i => glfw::SwapInterval::Sync(i as u32),
+        };
+        tracing::debug!(?swap_interval, "setting swap interval");
+        self.window.make_current();
+        self.glfw.set_swap_interval(swap_interval);
+    }
     pub fn is_opengl(&self) -> bool {
         let api = self.window.get_client_api();
         match api {
@@ -339,6 +757,370 @@ impl GlfwBackend {
             (size[1] * self.scale / self.physical_pixels_per_virtual_unit) as i32,
         );
     }
+    /// sets the window size such that the framebuffer is `size` physical pixels (eg: to match a capture region).
+    ///
+    /// glfw sets window size in virtual units, so we divide by [`Self::physical_pixels_per_virtual_unit`] and round.
+    /// If that ratio isn't an integer (eg: fractional scaling on wayland/macos), the resulting framebuffer size may be off by a pixel.
+    /// check [`Self::framebuffer_size_physical`] after the resize event for the actual size.
+    pub fn set_window_size_physical(&mut self, size: [u32; 2]) {
+        #[cfg(target_os = "emscripten")]
+        {
+            self.window.set_size(size[0] as i32, size[1] as i32);
+            // canvas style size is in css (logical) pixels
+            unsafe {
+                assert_eq!(
+                    emscripten_set_element_css_size(
+                        CANVAS_ELEMENT_NAME,
+                        (size[0] as f32 / self.scale) as _,
+                        (size[1] as f32 / self.scale) as _
+                    ),
+                    0
+                );
+            }
+        }
+        #[cfg(not(target_os = "emscripten"))]
+        self.window.set_size(
+            (size[0] as f32 / self.physical_pixels_per_virtual_unit).round() as i32,
+            (size[1] as f32 / self.physical_pixels_per_virtual_unit).round() as i32,
+        );
+    }
+    /// moves (warps) the os cursor to the position in logical points relative to the top left of the window.
+    /// we also update [`Self::cursor_pos`], so that the next `tick` won't see this as a cursor movement.
+    pub fn set_cursor_pos(&mut self, pos: [f32; 2]) {
+        self.window.set_cursor_pos(
+            (pos[0] * self.scale / self.physical_pixels_per_virtual_unit) as f64,
+            (pos[1] * self.scale / self.physical_pixels_per_virtual_unit) as f64,
+        );
+        self.cursor_pos = pos;
+    }
+    /// sets the unit used for scroll events. see [`Self::scroll_unit`]
+    pub fn set_scroll_unit(&mut self, unit: MouseWheelUnit) {
+        self.scroll_unit = unit;
+    }
+    /// loads the image file at `path` (png, ico, bmp or jpeg) and sets it as the window icon.
+    /// The image is also downscaled to common icon sizes (16, 32, 48 etc..), so that the window manager can pick the best fit
+    /// instead of scaling the full image by itself. The image should be square.
+    ///
+    /// If you don't want the image dependency, decode the icon yourself and use [`glfw::Window::set_icon_from_pixels`].
+    #[cfg(feature = "icon_from_path")]
+    pub fn set_icon_from_path(&mut self, path: &std::path::Path) -> Result<(), image::ImageError> {
+        const ICON_SIZES: [u32; 6] = [16, 32, 48, 64, 128, 256];
+        let icon = image::open(path)?.to_rgba8();
+        let to_pixel_image = |icon: &image::RgbaImage| glfw::PixelImage {
+            width: icon.width(),
+            height: icon.height(),
+            pixels: icon
+                .pixels()
+                .map(|pixel| u32::from_ne_bytes(pixel.0))
+                .collect(),
+        };
+        let mut icons: Vec<glfw::PixelImage> = ICON_SIZES
+            .into_iter()
+            .filter(|&size| size < icon.width().max(icon.height()))
+            .map(|size| {
+                to_pixel_image(&image::imageops::resize(
+                    &icon,
+                    size,
+                    size,
+                    image::imageops::FilterType::Lanczos3,
+                ))
+            })
+            .collect();
+        icons.push(to_pixel_image(&icon));
+        tracing::debug!(?path, icons = icons.len(), "setting window icon");
+        self.window.set_icon_from_pixels(icons);
+        Ok(())
+    }
+    /// minimizes the window
+    pub fn iconify(&mut self) {
+        self.window.iconify();
+    }
+    /// restores the window if it was minimized or maximized
+    pub fn restore(&mut self) {
+        self.window.restore();
+    }
+    /// maximizes the window
+    pub fn maximize(&mut self) {
+        self.window.maximize();
+    }
+    /// sets the opacity of the whole window (0.0 is fully transparent, 1.0 is opaque). cancels any ongoing fade.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity_fade = None;
+        self.window.set_opacity(opacity.clamp(0.0, 1.0));
+    }
+    /// gradually changes the window opacity from the current opacity to `target` over `duration`.
+    /// useful for auto-hiding HUDs. The fade is advanced in [`Self::tick`]. while [`Self::is_fading`] is true,
+    /// the event loop should keep running frames (instead of waiting for events), so that the fade progresses smoothly.
+    pub fn fade_to(&mut self, target: f32, duration: std::time::Duration) {
+        let target = target.clamp(0.0, 1.0);
+        if duration.is_zero() {
+            self.set_opacity(target);
+            return;
+        }
+        self.opacity_fade = Some(OpacityFade {
+            start_opacity: self.window.get_opacity(),
+            target_opacity: target,
+            start_time: self.time(),
+            duration: duration.as_secs_f64(),
+        });
+    }
+    /// whether an opacity fade started by [`Self::fade_to`] is still in progress
+    pub fn is_fading(&self) -> bool {
+        self.opacity_fade.is_some()
+    }
+    fn advance_fade(&mut self, time: f64) {
+        if let Some(fade) = self.opacity_fade {
+            let t = ((time - fade.start_time) / fade.duration).clamp(0.0, 1.0) as f32;
+            self.window
+                .set_opacity(fade.start_opacity + (fade.target_opacity - fade.start_opacity) * t);
+            if t >= 1.0 {
+                self.opacity_fade = None;
+            }
+        }
+    }
+    /// current video mode of the connected monitor at `monitor_index` (0 is the primary monitor).
+    /// returns None if there's no monitor at that index.
+    pub fn current_video_mode(&mut self, monitor_index: usize) -> Option<VideoMode> {
+        self.glfw.with_connected_monitors(|_, monitors| {
+            monitors
+                .get(monitor_index)
+                .and_then(|monitor| monitor.get_video_mode())
+                .map(VideoMode::from)
+        })
+    }
+    /// sets [`Self::refresh_rate_hint`], which [`Self::set_window_monitor`] passes to glfw when switching to [`FullscreenMode::Exclusive`]
+    /// without an explicit video mode. call this before the switch.
+    /// The monitor might not support the rate, in which case glfw picks the closest one.
+    pub fn set_refresh_rate_hint(&mut self, refresh_rate: Option<u32>) {
+        self.refresh_rate_hint = refresh_rate;
+    }
+    /// refresh rate (in Hz) of the video mode, while the window is in exclusive fullscreen. `None` if the window is not exclusive fullscreen
+    /// (use [`Self::current_video_mode`] of the monitor then). Use it to pick a matching present mode or swap interval for tear-free rendering.
+    pub fn current_refresh_rate(&self) -> Option<u32> {
+        self.window.with_window_mode(|mode| match mode {
+            glfw::WindowMode::FullScreen(monitor) => {
+                monitor.get_video_mode().map(|mode| mode.refresh_rate)
+            }
+            glfw::WindowMode::Windowed => None,
+        })
+    }
+    /// all video modes supported by the connected monitor at `monitor_index` (0 is the primary monitor).
+    /// returns an empty vec if there's no monitor at that index.
+    pub fn available_video_modes(&mut self, monitor_index: usize) -> Vec<VideoMode> {
+        self.glfw.with_connected_monitors(|_, monitors| {
+            monitors
+                .get(monitor_index)
+                .map(|monitor| {
+                    monitor
+                        .get_video_modes()
+                        .into_iter()
+                        .map(VideoMode::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+    /// moves the window to the monitor at `monitor_index` (0 is the primary monitor) with the `mode`.
+    /// handles the windowed <-> fullscreen and cross-monitor transitions in one call.
+    /// The windowed geometry is saved when leaving windowed mode and restored when `mode` is [`FullscreenMode::Windowed`]
+    /// (`monitor_index` is ignored in that case, unless there's no saved geometry).
+    /// returns false if there's no monitor at that index (or it has no video mode).
+    pub fn set_window_monitor(&mut self, monitor_index: usize, mode: FullscreenMode) -> bool {
+        if self.fullscreen_mode == FullscreenMode::Windowed && mode != FullscreenMode::Windowed {
+            let (x, y) = self.window.get_pos();
+            let (width, height) = self.window.get_size();
+            self.windowed_geometry = Some([x, y, width, height]);
+        }
+        let windowed_geometry = self.windowed_geometry;
+        let refresh_rate_hint = self.refresh_rate_hint;
+        let window = &mut self.window;
+        let done = self.glfw.with_connected_monitors(|_, monitors| {
+            let Some(monitor) = monitors.get(monitor_index) else {
+                return false;
+            };
+            let Some(current_mode) = monitor.get_video_mode() else {
+                return false;
+            };
+            let (monitor_x, monitor_y) = monitor.get_pos();
+            match mode {
+                FullscreenMode::Windowed => {
+                    // no saved geometry. so, just center a window with half the monitor size
+                    let [x, y, width, height] = windowed_geometry.unwrap_or([
+                        monitor_x + current_mode.width as i32 / 4,
+                        monitor_y + current_mode.height as i32 / 4,
+                        current_mode.width as i32 / 2,
+                        current_mode.height as i32 / 2,
+                    ]);
+                    window.set_monitor(
+                        glfw::WindowMode::Windowed,
+                        x,
+                        y,
+                        width as u32,
+                        height as u32,
+                        None,
+                    );
+                }
+                FullscreenMode::Borderless => {
+                    window.set_monitor(
+                        glfw::WindowMode::Windowed,
+                        monitor_x,
+                        monitor_y,
+                        current_mode.width,
+                        current_mode.height,
+                        None,
+                    );
+                }
+                FullscreenMode::Exclusive(video_mode) => {
+                    let video_mode = video_mode.unwrap_or_else(|| VideoMode {
+                        refresh_rate: refresh_rate_hint.unwrap_or(current_mode.refresh_rate),
+                        ..current_mode.into()
+                    });
+                    window.set_monitor(
+                        glfw::WindowMode::FullScreen(monitor),
+                        0,
+                        0,
+                        video_mode.width,
+                        video_mode.height,
+                        Some(video_mode.refresh_rate),
+                    );
+                }
+            }
+            true
+        });
+        if done {
+            tracing::info!(monitor_index, ?mode, "changed window monitor");
+            self.fullscreen_mode = mode;
+            if mode == FullscreenMode::Windowed {
+                self.windowed_geometry = None;
+            }
+        } else {
+            tracing::warn!(monitor_index, "failed to find monitor or its video mode");
+        }
+        done
+    }
+    /// [`Self::cursor_pos`] in physical pixels, relative to the window's framebuffer.
+    pub fn physical_cursor_pos(&self) -> [f32; 2] {
+        [
+            self.cursor_pos[0] * self.scale,
+            self.cursor_pos[1] * self.scale,
+        ]
+    }
+    /// cursor position in global screen coordinates (physical pixels), using the cached [`Self::window_position`].
+    /// The window position is in virtual units, so we convert it using [`Self::physical_pixels_per_virtual_unit`].
+    /// On wayland, windows can't know their global position, so this will be wrong.
+    /// see [`Self::global_cursor_pos_virtual`] if you need the cursor position in glfw's screen coordinates.
+    pub fn global_cursor_pos(&self) -> [f32; 2] {
+        let [x, y] = self.physical_cursor_pos();
+        [
+            self.window_position[0] as f32 * self.physical_pixels_per_virtual_unit + x,
+            self.window_position[1] as f32 * self.physical_pixels_per_virtual_unit + y,
+        ]
+    }
+    /// cursor position in global screen coordinates (virtual units).
+    /// glfw only gives us the cursor position relative to the window, so we add the window position to it.
+    /// On wayland, windows can't know their global position, so this will be wrong.
+    pub fn global_cursor_pos_virtual(&self) -> [f64; 2] {
+        let (window_x, window_y) = self.window.get_pos();
+        let (cursor_x, cursor_y) = self.window.get_cursor_pos();
+        [window_x as f64 + cursor_x, window_y as f64 + cursor_y]
+    }
+    /// moves and resizes the window to cover the workarea of the monitor which contains the cursor.
+    /// useful to open the overlay on whichever monitor the user is currently using.
+    /// returns false if no monitor contains the cursor (eg: on wayland, where we don't know the global cursor position).
+    pub fn move_to_cursor_monitor(&mut self) -> bool {
+        let [cursor_x, cursor_y] = self.global_cursor_pos_virtual();
+        let workarea = self.glfw.with_connected_monitors(|_, monitors| {
+            monitors
+                .iter()
+                .map(|monitor| monitor.get_workarea())
+                .find(|&(x, y, width, height)| {
+                    cursor_x >= x as f64
+                        && cursor_y >= y as f64
+                        && cursor_x < (x + width) as f64
+                        && cursor_y < (y + height) as f64
+                })
+        });
+        match workarea {
+            Some((x, y, width, height)) => {
+                info!(
+                    x,
+                    y, width, height, "moving window to the monitor under the cursor"
+                );
+                self.window.set_pos(x, y);
+                self.window.set_size(width, height);
+                true
+            }
+            None => {
+                tracing::warn!(
+                    cursor_x,
+                    cursor_y,
+                    "failed to find the monitor under the cursor"
+                );
+                false
+            }
+        }
+    }
+    /// wakes up the event loop if it is waiting for events.
+    /// glfw is not `Send`, so use [`Self::event_loop_waker`] to do this from other threads.
+    pub fn post_empty_event(&self) {
+        self.glfw.post_empty_event();
+    }
+    /// returns a handle that can be sent to other threads to wake up the event loop. see [`EventLoopWaker::wake`]
+    pub fn event_loop_waker(&self) -> EventLoopWaker {
+        EventLoopWaker { _private: () }
+    }
+    /// whether [`Self::set_clipboard_image`] is supported. use this to disable "Copy image" buttons etc..
+    /// only true if `clipboard_image` feature is enabled and we are not on emscripten.
+    pub fn clipboard_supports_images() -> bool {
+        cfg!(all(
+            feature = "clipboard_image",
+            not(target_os = "emscripten")
+        ))
+    }
+    /// copies the image to the system clipboard. returns false if it failed (or is not supported).
+    /// see [`Self::clipboard_supports_images`]
+    pub fn set_clipboard_image(&mut self, image: egui::ColorImage) -> bool {
+        #[cfg(all(feature = "clipboard_image", not(target_os = "emscripten")))]
+        {
+            if self.clipboard.is_none() {
+                match arboard::Clipboard::new() {
+                    Ok(clipboard) => self.clipboard = Some(clipboard),
+                    Err(e) => {
+                        tracing::error!("failed to create clipboard: {e}");
+                        return false;
+                    }
+                }
+            }
+            let clipboard = self.clipboard.as_mut().expect("clipboard must be created");
+            // egui colors are premultiplied, but clipboard expects unmultiplied rgba
+            let bytes: Vec<u8> = image
+                .pixels
+                .iter()
+                .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+                .collect();
+            if let Err(e) = clipboard.set_image(arboard::ImageData {
+                width: image.width(),
+                height: image.height(),
+                bytes: bytes.into(),
+            }) {
+                tracing::error!("failed to set clipboard image: {e}");
+                return false;
+            }
+            true
+        }
+        #[cfg(not(all(feature = "clipboard_image", not(target_os = "emscripten"))))]
+        {
+            let _ = image;
+            tracing::warn!(
+                "clipboard images are not supported. enable the clipboard_image feature"
+            );
+            false
+        }
+    }
+    /// replaces the mouse button remap table. see [`Self::mouse_button_remap`]
+    pub fn set_mouse_button_remap(&mut self, remap: HashMap<glfw::MouseButton, PointerButton>) {
+        self.mouse_button_remap = remap;
+    }
     pub fn set_title(&mut self, title: String) {
         self.title = title;
         self.window.set_title(&self.title);
@@ -346,22 +1128,186 @@ impl GlfwBackend {
     pub fn is_passthrough(&self) -> bool {
         self.passthrough
     }
+    /// pushes a synthesized release event for a button in [`Self::pressed_mouse_buttons`] and stops tracking it.
+    fn release_pressed_mouse_button(&mut self, mb: glfw::MouseButton) {
+        if let Some(button) = self.pressed_mouse_buttons.remove(&mb) {
+            tracing::debug!(?mb, "synthesizing missed mouse button release");
+            self.raw_input.events.push(Event::PointerButton {
+                pos: self.cursor_pos.into(),
+                button,
+                pressed: false,
+                modifiers: self.current_modifiers,
+            });
+        }
+    }
+    /// enables/disables [`Self::auto_passthrough`]. The content rects must be updated every frame with [`Self::content_rects`]
+    /// (`egui_overlay` does this for you, using [`content_rects_from_meshes`]).
+    /// When disabled, the window is made interactive (not passthrough) again.
+    pub fn set_auto_passthrough_from_content(&mut self, enabled: bool) {
+        self.auto_passthrough = enabled;
+        if !enabled {
+            self.content_rects.clear();
+            self.passthrough_controller.region = None;
+            self.apply_passthrough();
+        }
+    }
+    /// whether the window takes keyboard focus when it is shown (glfw's `FocusOnShow` attribute). This is independent of mouse passthrough,
+    /// so a passthrough overlay can still be focused (eg: with [`Self::focus_keyboard`] after a hotkey) and receive keyboard input.
+    ///
+    /// platform differences:
+    /// - windows/macos: a passthrough window can be focused and will receive key events while focused.
+    /// - x11: depends on the window manager. some WMs refuse to focus windows with an empty input shape.
+    /// - wayland: the compositor decides focus. windows can't focus themselves.
+    ///
+    /// Keep in mind that unfocused windows never receive key events on any platform. So, truly global hotkeys (while a game is focused)
+    /// need a platform specific api (eg: `RegisterHotKey` on windows), which glfw doesn't provide.
+    pub fn set_keyboard_focusable(&mut self, focusable: bool) {
+        self.window.set_focus_on_show(focusable);
+    }
+    /// "global" keyboard capture for overlays controlled entirely by keyboard (eg: navigating a list with arrow keys while passthrough).
+    /// When enabled, the window takes the keyboard focus now, and [`Self::tick`] takes it back whenever the cursor is over [`Self::content_rects`].
+    /// So, key events go to egui while mouse clicks still pass through to the windows below.
+    /// We don't fight for the focus when the cursor is elsewhere, so the user can still alt-tab or use the underlying app (which gets the keys then).
+    /// The content rects must be updated every frame (`egui_overlay` does this for you).
+    ///
+    /// glfw can't separate keyboard focus from the window focus. So, the underlying app loses focus while the overlay has it.
+    /// see [`Self::set_keyboard_focusable`] for platform differences (eg: on wayland, the window can't take the focus by itself).
+    pub fn set_keyboard_capture(&mut self, capture: bool) {
+        self.keyboard_capture = capture;
+        if capture {
+            self.set_keyboard_focusable(true);
+            self.focus_keyboard();
+        }
+    }
+    /// brings the window to front and gives it keyboard focus (if the platform allows it). see [`Self::set_keyboard_focusable`]
+    pub fn focus_keyboard(&mut self) {
+        self.window.focus();
+    }
+    /// explicitly pause (or resume) rendering, regardless of egui's repaint requests. eg: when the app knows that nothing changed.
+    /// While disabled, egui's repaint deadlines and fades don't wake the event loop.
+    /// Only input or `egui::Context::request_repaint` still run the gui (without drawing).
+    /// When enabled again, we request a refresh to draw a fresh frame immediately.
+    pub fn set_rendering_enabled(&mut self, enabled: bool) {
+        if self.rendering_enabled == enabled {
+            return;
+        }
+        tracing::debug!(enabled, "setting rendering enabled");
+        self.rendering_enabled = enabled;
+        if enabled {
+            self.refresh_requested = true;
+        }
+    }
+    /// hide or show the window without destroying it (eg: a tray resident overlay).
+    /// While hidden, [`Self::should_render`] is false. So, the event loop keeps processing events without rendering.
+    /// To show the window from another thread, set some flag and wake up the event loop with `egui::Context::request_repaint`
+    /// and then call this from your gui function.
+    pub fn set_visible(&mut self, visible: bool) {
+        // if the window is waiting for its first frame, it is hidden anyway. we just take over the decision.
+        let pending_first_frame = std::mem::take(&mut self.show_after_first_frame);
+        if self.visible == visible && !pending_first_frame {
+            return;
+        }
+        tracing::debug!(visible, "setting window visibility");
+        self.visible = visible;
+        if visible {
+            self.window.show();
+            // draw a fresh frame, as the last one might be outdated
+            self.refresh_requested = true;
+        } else {
+            self.window.hide();
+        }
+    }
+    /// whether the event loop should draw frames. false if rendering is disabled ([`Self::set_rendering_enabled`])
+    /// or if the window is hidden ([`Self::set_visible`]).
+    pub fn should_render(&self) -> bool {
+        self.rendering_enabled && self.visible
+    }
+    /// For overlays which never need input (pure visualizations, fps counters etc..).
+    /// The window is made passthrough permanently with an explicit override ([`PassthroughController::explicit`]),
+    /// so the gui ([`Self::set_passthrough`]) or content regions can't make it interactive and it never steals focus.
+    /// disabling it removes the explicit override.
+    ///
+    /// egui interaction (clicks, typing) is effectively disabled in this mode. But we still simulate cursor moved events
+    /// while the cursor is within the window bounds, so hover effects keep working.
+    pub fn set_always_passthrough(&mut self, always: bool) {
+        self.set_passthrough_override(always.then_some(true));
+    }
+    /// whether the window is forced to be passthrough by an explicit override. see [`Self::set_always_passthrough`]
+    pub fn is_always_passthrough(&self) -> bool {
+        self.passthrough_controller.explicit == Some(true)
+    }
+    /// flips the passthrough state and returns the new state. eg: for a "lock/unlock interaction" hotkey.
+    /// uses the cached [`Self::passthrough`], so it doesn't query the OS.
+    /// The new state is set as an explicit override, so it sticks until you call [`Self::set_passthrough_override`] with `None`.
+    pub fn toggle_passthrough(&mut self) -> bool {
+        self.set_passthrough_override(Some(!self.passthrough));
+        self.passthrough
+    }
+    /// sets [`PassthroughController::explicit`]. `Some` overrides content regions and the gui, `None` gives the decision back to them.
+    pub fn set_passthrough_override(&mut self, passthrough: Option<bool>) {
+        self.passthrough_controller.explicit = passthrough;
+        self.apply_passthrough();
+    }
+    /// sets [`PassthroughController::egui`], which is the lowest precedence. So, this won't change anything
+    /// while there's an explicit override or while [`Self::auto_passthrough`] is deciding based on content regions.
     pub fn set_passthrough(&mut self, passthrough: bool) {
+        self.passthrough_controller.egui = Some(passthrough);
+        self.apply_passthrough();
+    }
+    /// makes the window passthrough (or not) based on [`PassthroughController::decide`].
+    /// The window is only touched if the decision differs from the cached [`Self::passthrough`].
+    /// called by all the setters. So, you only need to call this if you modify [`Self::passthrough_controller`] directly.
+    pub fn apply_passthrough(&mut self) {
+        let passthrough = self.passthrough_controller.decide();
         if self.passthrough == passthrough {
             return;
         }
+        tracing::trace!(passthrough, controller = ?self.passthrough_controller, "setting mouse passthrough");
         self.window.set_mouse_passthrough(passthrough);
         self.passthrough = passthrough;
     }
+    /// If sticky keys are enabled, a key press will be "remembered" by glfw until it is polled with [`glfw::Window::get_key`],
+    /// even if it was released before polling.
+    ///
+    /// This only affects polling (eg: [`Self::is_key_down`]). egui input is built from key events, which are never missed anyway.
+    /// The sticky state is cleared when you poll the key, so a quick tap is reported once by the next poll after it happened.
+    pub fn set_sticky_keys(&mut self, sticky: bool) {
+        self.window.set_sticky_keys(sticky);
+    }
+    /// same as [`Self::set_sticky_keys`], but for mouse buttons. see [`Self::is_mouse_button_down`]
+    pub fn set_sticky_mouse_buttons(&mut self, sticky: bool) {
+        self.window.set_sticky_mouse_buttons(sticky);
+    }
+    /// whether CapsLock was on during the latest key/mouse button event.
+    /// lock key modifiers are only reported on non-emscripten platforms.
+    pub fn is_caps_lock_on(&self) -> bool {
+        self.modifiers.contains(Modifiers::CapsLock)
+    }
+    /// whether NumLock was on during the latest key/mouse button event.
+    /// lock key modifiers are only reported on non-emscripten platforms.
+    pub fn is_num_lock_on(&self) -> bool {
+        self.modifiers.contains(Modifiers::NumLock)
+    }
+    /// polls the current state of the key. This bypasses egui, so it works even if egui wants keyboard input.
+    /// useful for continuous stuff like movement (is W held right now?), without tracking press/release events yourself.
+    pub fn is_key_down(&self, key: glfw::Key) -> bool {
+        self.window.get_key(key) == glfw::Action::Press
+    }
+    /// polls the current state of the mouse button. Like [`Self::is_key_down`], this bypasses egui.
+    pub fn is_mouse_button_down(&self, button: glfw::MouseButton) -> bool {
+        self.window.get_mouse_button(button) == glfw::Action::Press
+    }
 }
 
 impl GlfwBackend {
     #[allow(unused)]
     pub fn tick(&mut self) {
         self.frame_events.clear();
+        self.frame_event_timestamps.clear();
 
-        let time = self.glfw.get_time();
+        let time = self.time();
         self.raw_input.time = Some(time);
+        self.advance_fade(time);
         self.raw_input.focused = self.focused;
 
         // whether we got a cursor event in this frame.
@@ -370,8 +1316,50 @@ impl GlfwBackend {
         let mut cursor_event = false;
         let mut scale = None;
         let mut close = false;
-        for (_timestamp, event) in glfw::flush_messages(&self.events_receiver) {
+        for (timestamp, event) in glfw::flush_messages(&self.events_receiver) {
+            if let Some(on_raw_event) = self.on_raw_event.as_mut() {
+                if on_raw_event(&event) {
+                    tracing::trace!(?event, "raw event consumed by hook");
+                    continue;
+                }
+            }
             self.frame_events.push(event.clone());
+            self.frame_event_timestamps.push(timestamp);
+            // keep track of latest modifiers (including lock keys)
+            if let WindowEvent::Key(_, _, _, m)
+            | WindowEvent::CharModifiers(_, m)
+            | WindowEvent::MouseButton(_, _, m) = &event
+            {
+                self.modifiers = *m;
+                self.current_modifiers = glfw_to_egui_modifers(*m);
+            }
+            // on some platforms, the modifiers of a modifier key's own event don't include that key's state yet.
+            if let WindowEvent::Key(k, _, a, _) = &event {
+                if let Some(pressed) = glfw_to_egui_action(*a) {
+                    match k {
+                        glfw::Key::LeftControl | glfw::Key::RightControl => {
+                            self.current_modifiers.ctrl = pressed;
+                            if !cfg!(target_os = "macos") {
+                                self.current_modifiers.command = pressed;
+                            }
+                        }
+                        // on mac, command is the cmd (super) key
+                        glfw::Key::LeftSuper | glfw::Key::RightSuper
+                            if cfg!(target_os = "macos") =>
+                        {
+                            self.current_modifiers.mac_cmd = pressed;
+                            self.current_modifiers.command = pressed;
+                        }
+                        glfw::Key::LeftShift | glfw::Key::RightShift => {
+                            self.current_modifiers.shift = pressed;
+                        }
+                        glfw::Key::LeftAlt | glfw::Key::RightAlt => {
+                            self.current_modifiers.alt = pressed;
+                        }
+                        _ => {}
+                    }
+                }
+            }
             // if let &glfw::WindowEvent::CursorPos(..) = &event {
             //     continue;
             // }
@@ -432,28 +1420,43 @@ impl GlfwBackend {
                     );
                     None
                 }
-                glfw::WindowEvent::MouseButton(mb, a, m) => {
-                    self.modifiers = m;
+                glfw::WindowEvent::MouseButton(mb, a, _) => {
+                    let button = self
+                        .mouse_button_remap
+                        .get(&mb)
+                        .copied()
+                        .unwrap_or_else(|| glfw_to_egui_pointer_button(mb));
+                    let pressed = glfw_to_egui_action(a).unwrap_or_default();
+                    // a press without a release in between. we must have missed the release.
+                    if pressed && self.pressed_mouse_buttons.contains_key(&mb) {
+                        self.release_pressed_mouse_button(mb);
+                    }
+                    if pressed {
+                        self.pressed_mouse_buttons.insert(mb, button);
+                    } else if self.pressed_mouse_buttons.remove(&mb).is_none() {
+                        // we already synthesized the release of this button. see `pressed_mouse_buttons`
+                        tracing::trace!(?mb, "ignoring release of an already released button");
+                        continue;
+                    }
                     let emb = Event::PointerButton {
                         pos: Pos2 {
                             x: self.cursor_pos[0],
                             y: self.cursor_pos[1],
                         },
-                        button: glfw_to_egui_pointer_button(mb),
-                        pressed: glfw_to_egui_action(a).unwrap_or_default(),
-                        modifiers: glfw_to_egui_modifers(m),
+                        button,
+                        pressed,
+                        modifiers: self.current_modifiers,
                     };
                     Some(emb)
                 }
                 glfw::WindowEvent::Scroll(x, y) => Some(Event::MouseWheel {
-                    unit: MouseWheelUnit::Point,
+                    unit: self.scroll_unit,
                     delta: [x as f32, y as f32].into(),
-                    modifiers: glfw_to_egui_modifers(self.modifiers),
+                    modifiers: self.current_modifiers,
                 }),
-                glfw::WindowEvent::Key(k, scancode, a, m) => match k {
+                glfw::WindowEvent::Key(k, scancode, a, _) => match k {
                     glfw::Key::C => {
-                        if glfw_to_egui_action(a).unwrap_or_default()
-                            && m.contains(glfw::Modifiers::Control)
+                        if glfw_to_egui_action(a).unwrap_or_default() && self.current_modifiers.ctrl
                         {
                             Some(Event::Copy)
                         } else {
@@ -461,8 +1464,7 @@ impl GlfwBackend {
                         }
                     }
                     glfw::Key::X => {
-                        if glfw_to_egui_action(a).unwrap_or_default()
-                            && m.contains(glfw::Modifiers::Control)
+                        if glfw_to_egui_action(a).unwrap_or_default() && self.current_modifiers.ctrl
                         {
                             Some(Event::Cut)
                         } else {
@@ -470,8 +1472,7 @@ impl GlfwBackend {
                         }
                     }
                     glfw::Key::V => {
-                        if glfw_to_egui_action(a).unwrap_or_default()
-                            && m.contains(glfw::Modifiers::Control)
+                        if glfw_to_egui_action(a).unwrap_or_default() && self.current_modifiers.ctrl
                         {
                             Some(Event::Text(
                                 self.window.get_clipboard_string().unwrap_or_default(),
@@ -487,7 +1488,7 @@ impl GlfwBackend {
                     layout_based_glfw_to_egui_key(k, scancode).map(|key| Event::Key {
                         key,
                         pressed: pressed.unwrap_or_default(),
-                        modifiers: glfw_to_egui_modifers(m),
+                        modifiers: self.current_modifiers,
                         repeat: false,
                         // glfw's keys have always been independent of layout
                         // if you need the key from the current layotu
@@ -495,12 +1496,16 @@ impl GlfwBackend {
                     })
                 }),
                 glfw::WindowEvent::Char(c) => Some(Event::Text(c.to_string())),
-                glfw::WindowEvent::ContentScale(x, _) => {
+                glfw::WindowEvent::ContentScale(x, y) => {
                     tracing::info!(
-                        previous_scale = self.scale,
-                        current_scale = x,
+                        previous_scale = ?self.content_scale,
+                        current_scale = ?[x, y],
                         "content scale changed"
                     );
+                    if x != y {
+                        tracing::warn!(x, y, "content scale differs per axis. egui will use the x axis scale for both");
+                    }
+                    self.content_scale = [x, y];
                     self.scale = x;
                     scale = Some(x);
                     self.window_size_logical = [
@@ -516,8 +1521,12 @@ impl GlfwBackend {
                         self.framebuffer_size_physical[0] as f32 / virtual_width as f32;
                     None
                 }
+                glfw::WindowEvent::Refresh => {
+                    self.refresh_requested = true;
+                    None
+                }
                 glfw::WindowEvent::Close => {
-                    self.window.set_should_close(true);
+                    self.request_close(CloseReason::WindowManager);
                     close = true;
                     None
                 }
@@ -559,26 +1568,44 @@ impl GlfwBackend {
                 }
                 WindowEvent::CursorEnter(c) => {
                     self.cursor_inside_bounds = c;
-                    #[cfg(not(target_os = "emscripten"))]
-                    if c {
-                        None
-                    } else if !self.window.is_mouse_passthrough() {
-                        // if window is not passthrough, then we forward the event.
-                        Some(Event::PointerGone)
-                    } else {
-                        // if it is passthrough, then we will let the simulated event take care of this
-                        // because the pointer might still be within bounds even if we get cursor left event due to window losing focus due to passthrough
-                        None
-                    }
+                    // passthrough doesn't mean anything on web. so, leaving the canvas always means the pointer is gone.
                     #[cfg(target_os = "emscripten")]
-                    if c {
-                        None
-                    } else {
-                        Some(Event::PointerGone)
-                    }
+                    let passthrough = false;
+                    #[cfg(not(target_os = "emscripten"))]
+                    let passthrough = self.window.is_mouse_passthrough();
+                    cursor_enter_to_egui_event(c, passthrough)
+                        .filter(|_| self.emit_pointer_gone_on_leave)
                 }
                 WindowEvent::Focus(f) => {
                     self.focused = f;
+                    // we won't get the release events of the buttons held while losing focus
+                    if !f {
+                        let buttons: Vec<_> = self.pressed_mouse_buttons.keys().copied().collect();
+                        for mb in buttons {
+                            self.release_pressed_mouse_button(mb);
+                        }
+                    }
+                    None
+                }
+                WindowEvent::Iconify(i) => {
+                    self.iconified = i;
+                    None
+                }
+                WindowEvent::Maximize(m) => {
+                    self.maximized = m;
+                    // on windows, maximizing doesn't always emit a framebuffer size event immediately.
+                    // so, we query the size ourselves to avoid rendering at the old size.
+                    let (width, height) = self.window.get_framebuffer_size();
+                    let (virtual_width, _) = self.window.get_size();
+                    let fb_size = [width as u32, height as u32];
+                    if fb_size != self.framebuffer_size_physical && virtual_width > 0 {
+                        self.framebuffer_size_physical = fb_size;
+                        self.resized_event_pending = true;
+                        self.physical_pixels_per_virtual_unit = width as f32 / virtual_width as f32;
+                        self.window_size_logical =
+                            [width as f32 / self.scale, height as f32 / self.scale];
+                        tracing::info!(width, height, "framebuffer size changed after maximize");
+                    }
                     None
                 }
                 _ => None,
@@ -598,30 +1625,42 @@ impl GlfwBackend {
         // when there's no cursor event and window is passthrough, then, simulate mouse events
         #[cfg(not(target_os = "emscripten"))]
         if !cursor_event && self.window.is_mouse_passthrough() {
-            let window_bounds =
-                egui::Rect::from_two_pos(Default::default(), self.window_size_logical.into());
-            // if cursor within window bounds
-            if window_bounds.contains(logical_cursor_pos.into()) {
-                // if cursor position has changed since last frame.
-                if logical_cursor_pos != self.cursor_pos {
-                    // we will manually push the cursor moved event.
-                    self.raw_input.events.push(Event::PointerMoved(
-                        [logical_cursor_pos[0], logical_cursor_pos[1]].into(),
-                    ));
-                }
-                self.cursor_inside_bounds = true;
-            } else {
-                // if present cursor is out of bounds for the first time, we need to simulate a pointer gone event.
-                // we use the cursor inside bounds flag to keep track of whether the cursor was active
-                if self.cursor_inside_bounds {
-                    self.raw_input.events.push(Event::PointerGone);
-                    // will only be true if we set a new pointermoved event using window event loop or cursor coming into bounds again.
-                    self.cursor_inside_bounds = false;
-                }
-            }
+            let events = simulate_passthrough_cursor(
+                self.window_size_logical,
+                self.cursor_pos,
+                logical_cursor_pos,
+                &mut self.cursor_inside_bounds,
+                &mut self.pressed_mouse_buttons,
+                self.current_modifiers,
+                self.emit_pointer_gone_on_leave,
+            );
+            self.raw_input.events.extend(events);
         }
         self.cursor_pos = logical_cursor_pos;
+        if self.auto_passthrough || self.keyboard_capture {
+            // content rects are in egui points, which are scaled by the fixed size zoom.
+            let zoom = self.fixed_size_zoom();
+            let egui_cursor_pos =
+                egui::pos2(logical_cursor_pos[0] / zoom, logical_cursor_pos[1] / zoom);
+            let over_content = self
+                .content_rects
+                .iter()
+                .any(|rect| rect.contains(egui_cursor_pos));
+            if self.auto_passthrough {
+                self.passthrough_controller.region = Some(!over_content);
+                self.apply_passthrough();
+            }
+            // only take the focus back while the cursor is over our content. Otherwise, we would fight alt-tab and the window manager.
+            if self.keyboard_capture && !self.focused && self.cursor_inside_bounds && over_content {
+                tracing::trace!(
+                    "cursor is over content while capturing keyboard. taking the focus"
+                );
+                self.window.focus();
+            }
+        }
+        self.raw_input.modifiers = self.current_modifiers;
         let title = self.title.clone();
+        let (inner_rect, outer_rect) = self.viewport_rects();
         let vp = self
             .raw_input
             .viewports
@@ -629,11 +1668,45 @@ impl GlfwBackend {
             .expect("failed to get default viewport info");
         vp.events.clear();
         vp.focused = Some(self.focused);
+        vp.minimized = Some(self.iconified);
+        vp.maximized = Some(self.maximized);
         vp.title = Some(title);
+        vp.inner_rect = Some(inner_rect);
+        vp.outer_rect = Some(outer_rect);
         if let Some(scale) = scale {
             vp.native_pixels_per_point = Some(scale);
         }
-        vp.events.push(ViewportEvent::Close);
+        if close {
+            vp.events.push(ViewportEvent::Close);
+        }
+    }
+    /// inner (client area) and outer (including decorations) rects of the window in monitor space, in egui points.
+    /// used to fill the root viewport info of raw input. On wayland, the window position is unknown, so they start at zero.
+    pub fn viewport_rects(&self) -> (egui::Rect, egui::Rect) {
+        // virtual units -> egui points
+        let points_per_virtual_unit =
+            self.physical_pixels_per_virtual_unit / self.scale / self.fixed_size_zoom();
+        let [x, y] = self.window_position;
+        let inner_min = egui::pos2(
+            x as f32 * points_per_virtual_unit,
+            y as f32 * points_per_virtual_unit,
+        );
+        let inner_rect =
+            egui::Rect::from_min_size(inner_min, self.egui_screen_size_logical().into());
+        let (left, top, right, bottom) = self.window.get_frame_size();
+        let outer_rect = egui::Rect::from_min_max(
+            inner_rect.min - egui::vec2(left as f32, top as f32) * points_per_virtual_unit,
+            inner_rect.max + egui::vec2(right as f32, bottom as f32) * points_per_virtual_unit,
+        );
+        (inner_rect, outer_rect)
+    }
+    /// Call this after presenting a frame (eg: after swapping buffers).
+    /// shows the window if it was created hidden using [`GlfwConfig::show_after_first_frame`]
+    pub fn on_frame_presented(&mut self) {
+        if self.show_after_first_frame {
+            self.show_after_first_frame = false;
+            self.window.show();
+        }
     }
     pub fn set_cursor(&mut self, cursor: egui::CursorIcon) {
         let cursor = egui_to_glfw_cursor(cursor);
@@ -643,6 +1716,111 @@ impl GlfwBackend {
         }
     }
 }
+/// minimum alpha of a vertex to be considered as "content" by [`content_rects_from_meshes`].
+/// This skips faint stuff like window shadows.
+pub const CONTENT_ALPHA_THRESHOLD: u8 = 128;
+
+/// computes the rects (in egui points) where egui drew something, for [`GlfwBackend::content_rects`].
+///
+/// egui merges shapes into a single mesh per clip rect and texture. So, we use the bounding box of all the vertices
+/// with alpha >= [`CONTENT_ALPHA_THRESHOLD`] in each mesh (clipped by its clip rect). paint callbacks use their whole clip rect.
+/// This is coarse (eg: a rounded window's corners will capture clicks), but cheap and good enough for most overlays.
+pub fn content_rects_from_meshes(meshes: &[egui::ClippedPrimitive]) -> Vec<egui::Rect> {
+    meshes
+        .iter()
+        .filter_map(|clipped_primitive| {
+            let rect = match &clipped_primitive.primitive {
+                egui::epaint::Primitive::Mesh(mesh) => {
+                    let mut bounds = egui::Rect::NOTHING;
+                    for vertex in mesh.vertices.iter() {
+                        if vertex.color.a() >= CONTENT_ALPHA_THRESHOLD {
+                            bounds.extend_with(vertex.pos);
+                        }
+                    }
+                    bounds
+                }
+                egui::epaint::Primitive::Callback(_) => clipped_primitive.clip_rect,
+            };
+            let rect = rect.intersect(clipped_primitive.clip_rect);
+            rect.is_positive().then_some(rect)
+        })
+        .collect()
+}
+
+/// simulates the cursor events of a passthrough window from the polled `cursor_pos` (logical), as glfw doesn't give us cursor events for it.
+/// 1. cursor within the `window_size` bounds: we push a `PointerMoved` if it moved since `previous_pos`.
+/// 2. cursor left the bounds: we push a `PointerGone` (if `emit_pointer_gone`).
+///
+/// `cursor_inside_bounds` is updated to keep track of whether the cursor was active.
+/// The `pressed_buttons` still held when the cursor crosses the bounds are released (and removed), as their release happens over another window
+/// and we will never get it. This keeps egui from getting stuck thinking that the button is held. see [`GlfwBackend::pressed_mouse_buttons`]
+pub fn simulate_passthrough_cursor(
+    window_size: [f32; 2],
+    previous_pos: [f32; 2],
+    cursor_pos: [f32; 2],
+    cursor_inside_bounds: &mut bool,
+    pressed_buttons: &mut HashMap<glfw::MouseButton, PointerButton>,
+    modifiers: egui::Modifiers,
+    emit_pointer_gone: bool,
+) -> Vec<Event> {
+    let mut events = vec![];
+    let window_bounds = egui::Rect::from_two_pos(Default::default(), window_size.into());
+    // releases are pushed at an outside position, so that they don't click anything.
+    let mut release_at = |pos: [f32; 2], events: &mut Vec<Event>| {
+        for (mb, button) in pressed_buttons.drain() {
+            tracing::debug!(?mb, "synthesizing missed mouse button release");
+            events.push(Event::PointerButton {
+                pos: pos.into(),
+                button,
+                pressed: false,
+                modifiers,
+            });
+        }
+    };
+    if window_bounds.contains(cursor_pos.into()) {
+        // coming back into bounds. the buttons held since the cursor left were released somewhere else.
+        if !*cursor_inside_bounds {
+            release_at(previous_pos, &mut events);
+        }
+        if cursor_pos != previous_pos {
+            events.push(Event::PointerMoved(cursor_pos.into()));
+        }
+        *cursor_inside_bounds = true;
+    } else if *cursor_inside_bounds {
+        // cursor is out of bounds for the first time.
+        release_at(cursor_pos, &mut events);
+        if emit_pointer_gone {
+            events.push(Event::PointerGone);
+        }
+        // will only be true if we get a new pointermoved event using window event loop or cursor coming into bounds again.
+        *cursor_inside_bounds = false;
+    }
+    events
+}
+
+/// why the window is closing. see [`GlfwBackend::close_reason`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// the window manager asked us to close. eg: titlebar close button, Alt+F4 or closing from the taskbar.
+    WindowManager,
+    /// the user asked to close from within the app. eg: a "Quit" button.
+    User,
+    /// the app decided to close by itself. eg: the game it's overlaying exited.
+    Programmatic,
+}
+
+/// translates glfw's `CursorEnter` event into egui event.
+/// 1. cursor entered: nothing to do, the cursor pos events will follow.
+/// 2. cursor left and window is not passthrough: we forward it as [`Event::PointerGone`].
+/// 3. cursor left and window is passthrough: we let the simulated events in [`GlfwBackend::tick`] take care of this.
+///    because the pointer might still be within bounds even if we get cursor left event due to window losing focus due to passthrough
+pub fn cursor_enter_to_egui_event(entered: bool, passthrough: bool) -> Option<Event> {
+    if entered || passthrough {
+        None
+    } else {
+        Some(Event::PointerGone)
+    }
+}
 /// glfw separates keys into two categories.
 /// 1. Printable
 /// 2. Non-Printable.
@@ -734,6 +1912,9 @@ pub fn layout_based_glfw_to_egui_key(key: glfw::Key, scancode: i32) -> Option<Ke
 }
 /// a function to get the matching egui key event for a given glfw key. egui does not support all the keys provided here.
 /// This just matches the enum to map to the relevant egui key.
+///
+/// egui 0.29 has no `PrintScreen`, `ScrollLock`, `Pause`, `Menu`, `CapsLock` (or modifier/numlock) keys, so they map to `None`.
+/// If you need them, read the raw glfw key events from [`GlfwBackend::frame_events`].
 pub fn layout_independent_glfw_to_egui_key(key: glfw::Key) -> Option<Key> {
     match key {
         glfw::Key::Space => Some(Key::Space),
@@ -796,7 +1977,10 @@ pub fn layout_independent_glfw_to_egui_key(key: glfw::Key) -> Option<Key> {
         glfw::Key::Backslash => Some(Key::Backslash),
         glfw::Key::RightBracket => Some(Key::CloseBracket),
         glfw::Key::KpAdd => Some(Key::Plus),
+        // commonly used as a console key in games
         glfw::Key::GraveAccent => Some(Key::Backtick),
+        glfw::Key::Apostrophe => Some(Key::Quote),
+        glfw::Key::Slash | glfw::Key::KpDivide => Some(Key::Slash),
         glfw::Key::F1 => Some(Key::F1),
         glfw::Key::F2 => Some(Key::F2),
         glfw::Key::F3 => Some(Key::F3),
@@ -817,17 +2001,30 @@ pub fn layout_independent_glfw_to_egui_key(key: glfw::Key) -> Option<Key> {
         glfw::Key::F18 => Some(Key::F18),
         glfw::Key::F19 => Some(Key::F19),
         glfw::Key::F20 => Some(Key::F20),
+        glfw::Key::F21 => Some(Key::F21),
+        glfw::Key::F22 => Some(Key::F22),
+        glfw::Key::F23 => Some(Key::F23),
+        glfw::Key::F24 => Some(Key::F24),
+        glfw::Key::F25 => Some(Key::F25),
+        // egui doesn't have PrintScreen, ScrollLock, Pause, Menu, CapsLock etc.. keys yet. see the doc comment above
         _ => None,
     }
 }
 
+/// on mac, [`egui::Modifiers::command`] and [`egui::Modifiers::mac_cmd`] come from the cmd (super) key.
+/// everywhere else, `command` is ctrl.
 pub fn glfw_to_egui_modifers(modifiers: glfw::Modifiers) -> egui::Modifiers {
+    let mac_cmd = cfg!(target_os = "macos") && modifiers.contains(glfw::Modifiers::Super);
     egui::Modifiers {
         alt: modifiers.contains(glfw::Modifiers::Alt),
         ctrl: modifiers.contains(glfw::Modifiers::Control),
         shift: modifiers.contains(glfw::Modifiers::Shift),
-        mac_cmd: false,
-        command: modifiers.contains(glfw::Modifiers::Control),
+        mac_cmd,
+        command: if cfg!(target_os = "macos") {
+            mac_cmd
+        } else {
+            modifiers.contains(glfw::Modifiers::Control)
+        },
     }
 }
 
@@ -923,3 +2120,175 @@ where
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_enter_native() {
+        // entering never emits anything. the cursor pos events will follow
+        assert_eq!(cursor_enter_to_egui_event(true, false), None);
+        assert_eq!(cursor_enter_to_egui_event(true, true), None);
+        // leaving an interactive window means the pointer is gone
+        assert_eq!(
+            cursor_enter_to_egui_event(false, false),
+            Some(Event::PointerGone)
+        );
+        // leaving a passthrough window is left to the simulation in `tick`
+        assert_eq!(cursor_enter_to_egui_event(false, true), None);
+    }
+
+    #[test]
+    fn cursor_enter_web() {
+        // on emscripten, `tick` always passes `passthrough = false`. so, leaving the canvas always emits `PointerGone`
+        let passthrough = false;
+        assert_eq!(cursor_enter_to_egui_event(true, passthrough), None);
+        assert_eq!(
+            cursor_enter_to_egui_event(false, passthrough),
+            Some(Event::PointerGone)
+        );
+    }
+
+    #[test]
+    fn release_outside_passthrough_window() {
+        let window_size = [100.0, 100.0];
+        let modifiers = egui::Modifiers::default();
+        let mut inside = true;
+        let mut pressed = HashMap::new();
+        // press inside. `tick` tracks the buttons it reports as pressed
+        pressed.insert(glfw::MouseButton::Button1, PointerButton::Primary);
+        // drag outside. the window is passthrough, so the release will happen over another app
+        let events = simulate_passthrough_cursor(
+            window_size,
+            [50.0, 50.0],
+            [150.0, 50.0],
+            &mut inside,
+            &mut pressed,
+            modifiers,
+            true,
+        );
+        assert_eq!(
+            events,
+            vec![
+                Event::PointerButton {
+                    pos: egui::pos2(150.0, 50.0),
+                    button: PointerButton::Primary,
+                    pressed: false,
+                    modifiers,
+                },
+                Event::PointerGone,
+            ]
+        );
+        assert!(!inside);
+        assert!(pressed.is_empty());
+        // release outside. glfw never tells us, and we don't release twice
+        let events = simulate_passthrough_cursor(
+            window_size,
+            [150.0, 50.0],
+            [160.0, 50.0],
+            &mut inside,
+            &mut pressed,
+            modifiers,
+            true,
+        );
+        assert!(events.is_empty());
+        // coming back only moves the pointer
+        let events = simulate_passthrough_cursor(
+            window_size,
+            [160.0, 50.0],
+            [50.0, 50.0],
+            &mut inside,
+            &mut pressed,
+            modifiers,
+            true,
+        );
+        assert_eq!(events, vec![Event::PointerMoved(egui::pos2(50.0, 50.0))]);
+        assert!(inside);
+    }
+
+    #[test]
+    fn release_on_reentering_passthrough_window() {
+        let modifiers = egui::Modifiers::default();
+        // the cursor left while the window was interactive (real `CursorEnter` event) with the button still held
+        let mut inside = false;
+        let mut pressed = HashMap::new();
+        pressed.insert(glfw::MouseButton::Button2, PointerButton::Secondary);
+        let events = simulate_passthrough_cursor(
+            [100.0, 100.0],
+            [150.0, 50.0],
+            [50.0, 50.0],
+            &mut inside,
+            &mut pressed,
+            modifiers,
+            true,
+        );
+        assert_eq!(
+            events,
+            vec![
+                Event::PointerButton {
+                    pos: egui::pos2(150.0, 50.0),
+                    button: PointerButton::Secondary,
+                    pressed: false,
+                    modifiers,
+                },
+                Event::PointerMoved(egui::pos2(50.0, 50.0)),
+            ]
+        );
+        assert!(pressed.is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs a display"]
+    fn raw_input_time_advances_between_ticks() {
+        let glfw = glfw::init(glfw::log_errors).expect("failed to init glfw");
+        let mut backend = GlfwBackend::new_with_glfw(
+            glfw,
+            GlfwConfig {
+                // keep the window hidden
+                show_after_first_frame: true,
+                ..Default::default()
+            },
+            None,
+        );
+        assert!(backend.raw_input.time.is_some());
+        assert!(backend.raw_input.viewports.contains_key(&ViewportId::ROOT));
+        backend.tick();
+        let first = backend
+            .take_raw_input()
+            .time
+            .expect("tick must set the time");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        backend.tick();
+        let second = backend
+            .take_raw_input()
+            .time
+            .expect("tick must set the time");
+        assert!(second > first, "time didn't advance: {first} -> {second}");
+    }
+
+    #[test]
+    fn punctuation_and_system_keys() {
+        // keys with an egui counterpart must be mapped. `None` means egui doesn't have a matching key (yet).
+        let keys = [
+            (glfw::Key::GraveAccent, Some(Key::Backtick)),
+            (glfw::Key::Apostrophe, Some(Key::Quote)),
+            (glfw::Key::Semicolon, Some(Key::Semicolon)),
+            (glfw::Key::Backslash, Some(Key::Backslash)),
+            (glfw::Key::LeftBracket, Some(Key::OpenBracket)),
+            (glfw::Key::RightBracket, Some(Key::CloseBracket)),
+            (glfw::Key::PrintScreen, None),
+            (glfw::Key::ScrollLock, None),
+            (glfw::Key::Pause, None),
+            (glfw::Key::Menu, None),
+            (glfw::Key::CapsLock, None),
+        ];
+        for (glfw_key, egui_key) in keys {
+            assert_eq!(
+                layout_independent_glfw_to_egui_key(glfw_key),
+                egui_key,
+                "{glfw_key:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+#![windows_subsystem = "windows"] // to turn off console.
+
+use egui_overlay::EguiOverlay;
+#[cfg(feature = "three_d")]
+use egui_render_three_d::ThreeDBackend as DefaultGfxBackend;
+#[cfg(feature = "wgpu")]
+use egui_render_wgpu::WgpuBackend as DefaultGfxBackend;
+
+#[cfg(not(any(feature = "three_d", feature = "wgpu")))]
+compile_error!("you must enable either `three_d` or `wgpu` feature to run this example");
+fn main() {
+    use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+    // if RUST_LOG is not set, we will use the following filters
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(
+            EnvFilter::try_from_default_env()
+                .unwrap_or(EnvFilter::new("debug,wgpu=warn,naga=warn")),
+        )
+        .init();
+
+    egui_overlay::start(ImageApp { image_size: None });
+}
+const IMAGE_BYTES: &[u8] = include_bytes!("../../kitty_icon.png");
+/// id of our user texture. egui will refer to it as `TextureId::User(KITTY_TEXTURE_ID)`
+const KITTY_TEXTURE_ID: u64 = 0;
+pub struct ImageApp {
+    /// size of the image in pixels. `None` until we upload the texture in the first frame.
+    pub image_size: Option<[u32; 2]>,
+}
+impl EguiOverlay for ImageApp {
+    fn gui_run(
+        &mut self,
+        egui_context: &egui::Context,
+        default_gfx_backend: &mut DefaultGfxBackend,
+        glfw_backend: &mut egui_window_glfw_passthrough::GlfwBackend,
+    ) {
+        // first frame logic. decode the png and upload it as a user texture
+        if self.image_size.is_none() {
+            let image = image::load_from_memory(IMAGE_BYTES)
+                .expect("failed to decode image")
+                .to_rgba8();
+            let size = [image.width(), image.height()];
+            #[cfg(feature = "three_d")]
+            unsafe {
+                let glow_backend = &mut default_gfx_backend.glow_backend;
+                glow_backend.painter.create_user_texture(
+                    &glow_backend.glow_context,
+                    KITTY_TEXTURE_ID,
+                    image.as_raw(),
+                    size,
+                    egui::TextureOptions::LINEAR,
+                    egui_render_three_d::ColorSpace::Srgb,
+                );
+            }
+            #[cfg(feature = "wgpu")]
+            default_gfx_backend.painter.create_user_texture(
+                &default_gfx_backend.device,
+                &default_gfx_backend.queue,
+                KITTY_TEXTURE_ID,
+                image.as_raw(),
+                size,
+                egui::TextureOptions::LINEAR,
+                egui_render_wgpu::wgpu::TextureFormat::Rgba8UnormSrgb,
+            );
+            self.image_size = Some(size);
+        }
+        let Some([width, height]) = self.image_size else {
+            return;
+        };
+        egui::Window::new("image").show(egui_context, |ui| {
+            ui.label(format!("user texture of size {width}x{height}"));
+            ui.image(egui::load::SizedTexture::new(
+                egui::TextureId::User(KITTY_TEXTURE_ID),
+                [width as f32, height as f32],
+            ));
+        });
+
+        // here you decide if you want to be passthrough or not.
+        if egui_context.wants_pointer_input() || egui_context.wants_keyboard_input() {
+            // we need input, so we need the window to be NOT passthrough
+            glfw_backend.set_passthrough(false);
+        } else {
+            // we don't care about input, so the window can be passthrough now
+            glfw_backend.set_passthrough(true)
+        }
+    }
+}
@@ -40,10 +40,7 @@ impl EguiOverlay for HelloWorld {
         if let Some(model) = &mut self.model {
             // Create a camera
             let camera = three_d::Camera::new_perspective(
-                Viewport::new_at_origo(
-                    glfw_backend.framebuffer_size_physical[0],
-                    glfw_backend.framebuffer_size_physical[1],
-                ),
+                three_d_backend.viewport(),
                 vec3(0.0, 0.0, 2.0),
                 vec3(0.0, 0.0, 0.0),
                 vec3(0.0, 1.0, 0.0),
@@ -52,18 +49,15 @@ impl EguiOverlay for HelloWorld {
                 10.0,
             );
             // Update the animation of the triangle
-            model.animate(glfw_backend.glfw.get_time() as _);
+            model.animate(glfw_backend.time() as _);
 
             // Get the screen render target to be able to render something on the screen
-            egui_render_three_d::three_d::RenderTarget::<'_>::screen(
-                &three_d_backend.context,
-                glfw_backend.framebuffer_size_physical[0],
-                glfw_backend.framebuffer_size_physical[1],
-            )
-            // Clear the color and depth of the screen render target. use transparent color.
-            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
-            // Render the triangle with the color material which uses the per vertex colors defined at construction
-            .render(&camera, std::iter::once(model), &[]);
+            three_d_backend
+                .screen_render_target()
+                // Clear the color and depth of the screen render target. use transparent color.
+                .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+                // Render the triangle with the color material which uses the per vertex colors defined at construction
+                .render(&camera, std::iter::once(model), &[]);
         }
         egui::Window::new("hello window")
             .scroll([true, true])
@@ -1,4 +1,10 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use egui::{Context, PlatformOutput};
 #[cfg(feature = "three_d")]
@@ -15,32 +21,114 @@ use egui_window_glfw_passthrough::{GlfwBackend, GlfwConfig};
 
 /// After implementing [`EguiOverlay`], just call this function with your app data
 pub fn start<T: EguiOverlay + 'static>(user_data: T) {
+    start_with_config(user_data, OverlayConfig::default());
+}
+
+/// Configuration for the overlay created by [`start_with_config`]
+#[derive(Debug, Default)]
+pub struct OverlayConfig {
+    /// If true, a panic during a frame (eg: inside your gui code or the renderer) will be caught.
+    /// The panic will be logged, the frame will be skipped and [`EguiOverlay::on_panic`] will be called.
+    /// default is false, which means the panic will just unwind and end the process like usual.
+    pub catch_panics: bool,
+    /// If set, egui's repaints (animations, `request_repaint` etc..) are throttled to at most one frame per this duration
+    /// while the window is unfocused. eg: `Duration::from_millis(100)` for 10 fps. saves battery when the user isn't looking at the overlay.
+    /// Input events still trigger a frame immediately, so interacting with the overlay stays responsive.
+    /// Keep in mind that overlays are often unfocused (eg: passthrough over a game), even when the user is looking at them.
+    pub unfocused_min_frame_time: Option<Duration>,
+    /// see [`GlfwConfig::show_after_first_frame`]
+    pub show_after_first_frame: bool,
+    /// see [`GlfwBackend::fixed_logical_size`]
+    pub fixed_logical_size: Option<[f32; 2]>,
+    /// If true, the overlay will be moved to (and resized to cover) the monitor which contains the mouse cursor at startup.
+    /// see [`OverlayApp::set_target_monitor_from_cursor`]
+    pub open_on_cursor_monitor: bool,
+    /// If set, these tessellation options are applied to the egui context at startup. eg: disable feathering for crisp pixel ui
+    /// (feathering can look muddy on HUDs rendered without MSAA). You can also change them later with [`Context::options_mut`].
+    pub tessellation_options: Option<egui::epaint::TessellationOptions>,
+    /// If set (and greater than 1.0), egui is rendered at this many times the window resolution and downsampled.
+    /// This makes the ui crisper without MSAA, at the cost of fill rate. The egui pixels_per_point is multiplied accordingly.
+    /// see `supersample_factor` of the gfx backend.
+    pub supersample_factor: Option<f32>,
+    /// If true, the font atlas is built and uploaded to the gpu at startup with [`prewarm_fonts`].
+    /// This avoids the hitch on the first frame that renders text.
+    pub prewarm_fonts: bool,
+    /// If true, the overlay window is not scaled by the monitor's content scale on creation.
+    /// useful for pixel-exact overlays which manage their size themselves. see [`GlfwConfig::scale_to_monitor`]
+    pub disable_scale_to_monitor: bool,
+    /// If set, the overlay will use this egui context instead of creating a new one.
+    /// useful if you already prepared a context (loaded memory/state, installed fonts etc..) or want to keep it across overlay restarts.
+    /// [`Self::tessellation_options`] are still applied on top of it, and the repaint callback is replaced by the overlay's event loop.
+    pub initial_egui_context: Option<Context>,
+    /// If set, egui memory (window positions, collapsed states etc..) is loaded from this file at startup
+    /// and saved to it when the overlay exits. see [`load_egui_memory`] and [`save_egui_memory`]
+    #[cfg(feature = "persistence")]
+    pub persistence_path: Option<std::path::PathBuf>,
+    /// If set, egui's output events of every frame are appended to this file as json lines. see [`OutputEventRecorder`]
+    /// useful for QA, as it gives a reproducible trace of interactions with named widgets.
+    #[cfg(feature = "event_log")]
+    pub event_log_path: Option<std::path::PathBuf>,
+    /// If set, the raw input of every frame is recorded to this file. see [`OverlayApp::record_input`]
+    #[cfg(feature = "input_recording")]
+    pub record_input_path: Option<std::path::PathBuf>,
+    /// If set, the input recorded in this file is replayed instead of the live input. see [`OverlayApp::replay_input`]
+    #[cfg(feature = "input_recording")]
+    pub replay_input_path: Option<std::path::PathBuf>,
+}
+
+/// Same as [`start`], but allows you to configure the overlay
+pub fn start_with_config<T: EguiOverlay + 'static>(user_data: T, config: OverlayConfig) {
+    let OverlayConfig {
+        catch_panics,
+        unfocused_min_frame_time,
+        show_after_first_frame,
+        fixed_logical_size,
+        open_on_cursor_monitor,
+        tessellation_options,
+        supersample_factor,
+        prewarm_fonts: should_prewarm_fonts,
+        disable_scale_to_monitor,
+        initial_egui_context,
+        #[cfg(feature = "persistence")]
+        persistence_path,
+        #[cfg(feature = "event_log")]
+        event_log_path,
+        #[cfg(feature = "input_recording")]
+        record_input_path,
+        #[cfg(feature = "input_recording")]
+        replay_input_path,
+    } = config;
     let mut glfw_backend = GlfwBackend::new(GlfwConfig {
         // this closure will be called before creating a window
         glfw_callback: Box::new(|gtx| {
             // some defualt hints. it is empty atm, but in future we might add some convenience hints to it.
             (egui_window_glfw_passthrough::GlfwConfig::default().glfw_callback)(gtx);
-            // scale the window size based on monitor scale. as 800x600 looks too small on a 4k screen, compared to a hd screen in absolute pixel sizes.
-            gtx.window_hint(egui_window_glfw_passthrough::glfw::WindowHint::ScaleToMonitor(true));
         }),
+        // scale the window size based on monitor scale. as 800x600 looks too small on a 4k screen, compared to a hd screen in absolute pixel sizes.
+        scale_to_monitor: Some(!disable_scale_to_monitor),
         #[cfg(feature = "three_d")]
         opengl_window: Some(true), // opengl for non-macos, for faster compilation and less wgpu bloat. also, drivers are better with gl transparency than vk
         #[cfg(feature = "wgpu")]
         opengl_window: Some(false), // macos doesn't support opengl.
         transparent_window: Some(true),
+        show_after_first_frame,
+        // always on top
+        floating: Some(true),
+        // disable borders/titlebar
+        decorated: Some(false),
         ..Default::default()
     });
-    // always on top
-    glfw_backend.window.set_floating(true);
-    // disable borders/titlebar
-    glfw_backend.window.set_decorated(false);
+    glfw_backend.fixed_logical_size = fixed_logical_size;
+    if open_on_cursor_monitor {
+        glfw_backend.move_to_cursor_monitor();
+    }
 
     let latest_size = glfw_backend.window.get_framebuffer_size();
     let latest_size = [latest_size.0 as _, latest_size.1 as _];
 
     // for non-macos, we just use three_d because its much faster compile times and opengl transparency being more reliable than vulkan transparency
     #[cfg(feature = "three_d")]
-    let default_gfx_backend = {
+    let mut default_gfx_backend = {
         DefaultGfxBackend::new(
             egui_render_three_d::ThreeDConfig {
                 ..Default::default()
@@ -52,22 +140,276 @@ pub fn start<T: EguiOverlay + 'static>(user_data: T) {
 
     // macos doesn't have opengl, so wgpu/metal for that.
     #[cfg(feature = "wgpu")]
-    let default_gfx_backend = DefaultGfxBackend::new(
+    let mut default_gfx_backend = DefaultGfxBackend::new(
         egui_render_wgpu::WgpuConfig {
             ..Default::default()
         },
         Some(Box::new(glfw_backend.window.render_context())),
         latest_size,
     );
-    let overlap_app = OverlayApp {
+    if let Some(supersample_factor) = supersample_factor {
+        #[cfg(feature = "three_d")]
+        default_gfx_backend.set_supersample_factor(supersample_factor);
+        #[cfg(feature = "wgpu")]
+        {
+            default_gfx_backend.supersample_factor = supersample_factor;
+        }
+    }
+    let egui_context = initial_egui_context.unwrap_or_default();
+    if let Some(tessellation_options) = tessellation_options {
+        egui_context.options_mut(|options| options.tessellation_options = tessellation_options);
+    }
+    if should_prewarm_fonts {
+        prewarm_fonts(&egui_context, &mut default_gfx_backend, &mut glfw_backend);
+    }
+    #[cfg(feature = "persistence")]
+    if let Some(path) = persistence_path.as_deref() {
+        load_egui_memory(&egui_context, path);
+    }
+    #[allow(unused_mut)]
+    let mut overlap_app = OverlayApp {
         user_data,
-        egui_context: Default::default(),
+        egui_context,
         default_gfx_backend,
         glfw_backend,
+        catch_panics,
+        unfocused_min_frame_time,
+        #[cfg(feature = "persistence")]
+        persistence_path,
+        #[cfg(feature = "event_log")]
+        output_event_recorder: event_log_path.as_deref().and_then(|path| {
+            OutputEventRecorder::create(path)
+                .inspect_err(|e| tracing::error!(?path, %e, "failed to create event log"))
+                .ok()
+        }),
+        #[cfg(feature = "input_recording")]
+        input_recorder: None,
+        #[cfg(feature = "input_recording")]
+        input_replay: None,
     };
+    #[cfg(feature = "input_recording")]
+    {
+        if let Some(path) = record_input_path.as_deref() {
+            if let Err(e) = overlap_app.record_input(path) {
+                tracing::error!(?path, %e, "failed to create input recording");
+            }
+        }
+        if let Some(path) = replay_input_path.as_deref() {
+            if let Err(e) = overlap_app.replay_input(path) {
+                tracing::error!(?path, %e, "failed to load input recording");
+            }
+        }
+    }
     overlap_app.enter_event_loop();
 }
 
+/// loads egui memory from a ron file (written by [`save_egui_memory`]) into the context.
+/// returns false (and logs the error) if the file couldn't be read or parsed. eg: on the first run, when the file doesn't exist yet.
+#[cfg(feature = "persistence")]
+pub fn load_egui_memory(egui_context: &Context, path: &std::path::Path) -> bool {
+    let memory = match std::fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<egui::Memory>(&contents) {
+            Ok(memory) => memory,
+            Err(e) => {
+                tracing::error!(?path, %e, "failed to parse egui memory");
+                return false;
+            }
+        },
+        Err(e) => {
+            tracing::warn!(?path, %e, "failed to read egui memory");
+            return false;
+        }
+    };
+    egui_context.memory_mut(|m| *m = memory);
+    tracing::info!(?path, "loaded egui memory");
+    true
+}
+/// saves egui memory of the context as a ron file. returns false (and logs the error) on failure.
+#[cfg(feature = "persistence")]
+pub fn save_egui_memory(egui_context: &Context, path: &std::path::Path) -> bool {
+    let contents = match egui_context.memory(ron::to_string) {
+        Ok(contents) => contents,
+        Err(e) => {
+            tracing::error!(%e, "failed to serialize egui memory");
+            return false;
+        }
+    };
+    if let Err(e) = std::fs::write(path, contents) {
+        tracing::error!(?path, %e, "failed to write egui memory");
+        return false;
+    }
+    tracing::info!(?path, "saved egui memory");
+    true
+}
+
+/// writes egui's [`egui::output::OutputEvent`]s to a file as json lines. one line per event: `{"time": 1.5, "event": {...}}`
+/// `time` is the glfw time (in seconds) of the frame which produced the event.
+#[cfg(feature = "event_log")]
+pub struct OutputEventRecorder {
+    pub writer: std::io::BufWriter<std::fs::File>,
+}
+#[cfg(feature = "event_log")]
+impl OutputEventRecorder {
+    /// creates (or truncates) the file at `path`
+    pub fn create(path: &std::path::Path) -> std::io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        tracing::info!(?path, "recording egui output events");
+        Ok(Self {
+            writer: std::io::BufWriter::new(file),
+        })
+    }
+    /// appends the events and flushes the file. So, the trace is complete even if the app crashes later.
+    pub fn record(
+        &mut self,
+        time: f64,
+        events: &[egui::output::OutputEvent],
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+        if events.is_empty() {
+            return Ok(());
+        }
+        for event in events {
+            let line = serde_json::json!({ "time": time, "event": event });
+            writeln!(self.writer, "{line}")?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// writes the raw input of every frame to a file as json lines (one [`egui::RawInput`] per line). see [`OverlayApp::record_input`]
+///
+/// The input is recorded as the glfw backend produced it, before the overlay applies zoom/supersampling to it.
+#[cfg(feature = "input_recording")]
+pub struct InputRecorder {
+    pub writer: std::io::BufWriter<std::fs::File>,
+}
+#[cfg(feature = "input_recording")]
+impl InputRecorder {
+    /// creates (or truncates) the file at `path`
+    pub fn create(path: &std::path::Path) -> std::io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        tracing::info!(?path, "recording input");
+        Ok(Self {
+            writer: std::io::BufWriter::new(file),
+        })
+    }
+    /// appends the frame's input and flushes the file. So, the recording is complete even if the app crashes later.
+    pub fn record(&mut self, raw_input: &egui::RawInput) -> std::io::Result<()> {
+        use std::io::Write;
+        serde_json::to_writer(&mut self.writer, raw_input)?;
+        writeln!(self.writer)?;
+        self.writer.flush()
+    }
+}
+/// plays back the frames recorded by [`InputRecorder`] at their recorded pace. see [`OverlayApp::replay_input`]
+///
+/// replay is only reproducible with the same window size and content scale as the recording.
+#[cfg(feature = "input_recording")]
+pub struct InputReplay {
+    pub frames: std::collections::VecDeque<egui::RawInput>,
+    /// (glfw time, recorded time) of the first replayed frame. used to pace the frames.
+    pub start: Option<(f64, f64)>,
+}
+#[cfg(feature = "input_recording")]
+impl InputReplay {
+    /// loads all the frames of a recording
+    pub fn load(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::io::BufRead;
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut frames = std::collections::VecDeque::new();
+        for line in file.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            frames.push_back(serde_json::from_str(&line)?);
+        }
+        tracing::info!(?path, frames = frames.len(), "loaded input recording");
+        Ok(Self {
+            frames,
+            start: None,
+        })
+    }
+    /// seconds (from `now`) until the next frame is due. `None` if the replay is finished.
+    pub fn time_until_next(&self, now: f64) -> Option<f64> {
+        let frame = self.frames.front()?;
+        let Some((start_time, start_recorded_time)) = self.start else {
+            return Some(0.0);
+        };
+        let recorded_time = frame.time.unwrap_or(start_recorded_time);
+        Some((recorded_time - start_recorded_time - (now - start_time)).max(0.0))
+    }
+    /// pops the next frame if it is due at glfw time `now`. The frame's time is replaced with `now`,
+    /// so that egui's clock keeps moving forward.
+    pub fn next_due(&mut self, now: f64) -> Option<egui::RawInput> {
+        if self.time_until_next(now)? > 0.0 {
+            return None;
+        }
+        let mut frame = self.frames.pop_front()?;
+        if self.start.is_none() {
+            self.start = Some((now, frame.time.unwrap_or_default()));
+        }
+        frame.time = Some(now);
+        Some(frame)
+    }
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// takes the raw input from glfw backend, and scales the pixels_per_point if the gfx backend is supersampling.
+fn take_egui_input(
+    glfw_backend: &mut GlfwBackend,
+    default_gfx_backend: &DefaultGfxBackend,
+) -> egui::RawInput {
+    let mut input = glfw_backend.take_raw_input();
+    // when supersampling, egui needs to render at a higher resolution. so, we scale its pixels_per_point.
+    #[cfg(feature = "three_d")]
+    let supersample_factor = default_gfx_backend.supersample_factor();
+    #[cfg(feature = "wgpu")]
+    let supersample_factor = default_gfx_backend.supersample_factor;
+    if supersample_factor > 1.0 {
+        if let Some(ppp) = input
+            .viewports
+            .get_mut(&egui::ViewportId::ROOT)
+            .and_then(|vp| vp.native_pixels_per_point.as_mut())
+        {
+            *ppp *= supersample_factor;
+        }
+    }
+    input
+}
+
+/// Builds the font atlas (with the printable ascii glyphs of all text styles) by running a throwaway egui pass,
+/// and uploads the resulting font texture to the gpu. Nothing is drawn or presented.
+///
+/// The first frame that renders text would otherwise rasterize glyphs and upload the whole font texture, causing a visible hitch.
+/// call this during initialization (or use [`OverlayConfig::prewarm_fonts`]).
+/// Any pending input events are kept for the next real frame.
+pub fn prewarm_fonts(
+    egui_context: &Context,
+    default_gfx_backend: &mut DefaultGfxBackend,
+    glfw_backend: &mut GlfwBackend,
+) {
+    let events = std::mem::take(&mut glfw_backend.raw_input.events);
+    let input = take_egui_input(glfw_backend, default_gfx_backend);
+    glfw_backend.raw_input.events = events;
+    egui_context.begin_pass(input);
+    let font_ids: Vec<egui::FontId> = egui_context.style().text_styles.values().cloned().collect();
+    let ascii: String = (' '..='~').collect();
+    egui_context.fonts(|fonts| {
+        for font_id in font_ids {
+            fonts.layout_no_wrap(ascii.clone(), font_id, egui::Color32::WHITE);
+        }
+    });
+    let textures_delta = egui_context.end_pass().textures_delta;
+    tracing::debug!(
+        textures = textures_delta.set.len(),
+        "uploading prewarmed font textures"
+    );
+    default_gfx_backend.upload_textures(textures_delta);
+}
+
 /// Implement this trait for your struct containing data you need. Then, call [`start`] fn with that data
 pub trait EguiOverlay {
     fn gui_run(
@@ -82,13 +424,18 @@ pub trait EguiOverlay {
         default_gfx_backend: &mut DefaultGfxBackend,
         glfw_backend: &mut GlfwBackend,
     ) -> Option<(PlatformOutput, Duration)> {
-        let input = glfw_backend.take_raw_input();
-        // takes a closure that can provide latest framebuffer size.
-        // because some backends like vulkan/wgpu won't work without reconfiguring the surface after some sort of resize event unless you give it the latest size
-        default_gfx_backend.prepare_frame(|| {
-            let latest_size = glfw_backend.window.get_framebuffer_size();
-            [latest_size.0 as _, latest_size.1 as _]
-        });
+        let mut input = take_egui_input(glfw_backend, default_gfx_backend);
+        self.on_events(&mut input.events);
+        // rendering might be toggled inside gui_run (or the window hidden). So, we remember whether we acquired a frame.
+        let rendering_enabled = glfw_backend.should_render();
+        if rendering_enabled {
+            // takes a closure that can provide latest framebuffer size.
+            // because some backends like vulkan/wgpu won't work without reconfiguring the surface after some sort of resize event unless you give it the latest size
+            default_gfx_backend.prepare_frame(|| {
+                let latest_size = glfw_backend.window.get_framebuffer_size();
+                [latest_size.0 as _, latest_size.1 as _]
+            });
+        }
         egui_context.begin_pass(input);
         self.gui_run(egui_context, default_gfx_backend, glfw_backend);
 
@@ -99,13 +446,33 @@ pub trait EguiOverlay {
             pixels_per_point,
             viewport_output,
         } = egui_context.end_pass();
-        let meshes = egui_context.tessellate(shapes, pixels_per_point);
         let repaint_after = viewport_output
             .into_iter()
             .map(|f| f.1.repaint_delay)
             .collect::<Vec<Duration>>()[0];
+        if !rendering_enabled || !glfw_backend.should_render() {
+            // egui won't send these texture updates again (eg: font atlas). so, we upload them without drawing.
+            default_gfx_backend.upload_textures(textures_delta);
+            // throw away the acquired surface image (if any) and any commands recorded by the user this frame
+            #[cfg(feature = "wgpu")]
+            default_gfx_backend.discard_frame();
+            return Some((platform_output, repaint_after));
+        }
+        let meshes = egui_context.tessellate(shapes, pixels_per_point);
+        if glfw_backend.auto_passthrough || glfw_backend.keyboard_capture {
+            glfw_backend.content_rects = if egui_context.is_using_pointer() {
+                // while dragging (eg: a window or slider), the pointer might leave the content. we don't want to lose it.
+                vec![egui::Rect::EVERYTHING]
+            } else {
+                egui_window_glfw_passthrough::content_rects_from_meshes(&meshes)
+            };
+        }
 
-        default_gfx_backend.render_egui(meshes, textures_delta, glfw_backend.window_size_logical);
+        default_gfx_backend.render_egui(
+            meshes,
+            textures_delta,
+            glfw_backend.egui_screen_size_logical(),
+        );
         if glfw_backend.is_opengl() {
             use egui_window_glfw_passthrough::glfw::Context;
             glfw_backend.window.swap_buffers();
@@ -114,8 +481,26 @@ pub trait EguiOverlay {
             #[cfg(feature = "wgpu")]
             default_gfx_backend.present()
         }
+        glfw_backend.on_frame_presented();
         Some((platform_output, repaint_after))
     }
+    /// This is called with the egui events of the frame (translated from glfw events), before the egui pass begins.
+    /// You can inspect them (analytics, recording) or modify them (input macros, injecting synthetic events).
+    fn on_events(&mut self, events: &mut Vec<egui::Event>) {
+        let _ = events;
+    }
+    /// This is called when the framebuffer is resized (after the gfx backend has been resized), before the next frame.
+    /// useful if you manage your own render targets (eg: a post-processing buffer) and want to resize them in lockstep.
+    /// `physical_size` is the framebuffer size in pixels and `scale` is the pixels per logical point.
+    fn on_resize(&mut self, physical_size: [u32; 2], scale: f32) {
+        let _ = (physical_size, scale);
+    }
+    /// This is called after a panic during [`Self::run`] is caught. see [`OverlayConfig::catch_panics`].
+    /// The frame is skipped, and the event loop continues after this returns.
+    /// `panic_message` is the message of the panic (if it was a string).
+    fn on_panic(&mut self, panic_message: &str) {
+        let _ = panic_message;
+    }
 }
 
 pub struct OverlayApp<T: EguiOverlay + 'static> {
@@ -123,21 +508,88 @@ pub struct OverlayApp<T: EguiOverlay + 'static> {
     pub egui_context: Context,
     pub default_gfx_backend: DefaultGfxBackend,
     pub glfw_backend: GlfwBackend,
+    /// see [`OverlayConfig::catch_panics`]
+    pub catch_panics: bool,
+    /// see [`OverlayConfig::unfocused_min_frame_time`]
+    pub unfocused_min_frame_time: Option<Duration>,
+    /// see [`OverlayConfig::persistence_path`]
+    #[cfg(feature = "persistence")]
+    pub persistence_path: Option<std::path::PathBuf>,
+    /// see [`OverlayConfig::event_log_path`]
+    #[cfg(feature = "event_log")]
+    pub output_event_recorder: Option<OutputEventRecorder>,
+    /// see [`Self::record_input`]
+    #[cfg(feature = "input_recording")]
+    pub input_recorder: Option<InputRecorder>,
+    /// see [`Self::replay_input`]
+    #[cfg(feature = "input_recording")]
+    pub input_replay: Option<InputReplay>,
 }
 
 impl<T: EguiOverlay + 'static> OverlayApp<T> {
+    /// moves the overlay to the monitor which contains the mouse cursor, and resizes it to cover that monitor's workarea.
+    /// returns false if the monitor couldn't be found. see [`GlfwBackend::move_to_cursor_monitor`]
+    pub fn set_target_monitor_from_cursor(&mut self) -> bool {
+        self.glfw_backend.move_to_cursor_monitor()
+    }
+    /// records the raw input of every frame (that runs the gui) to the file at `path`. see [`InputRecorder`]
+    #[cfg(feature = "input_recording")]
+    pub fn record_input(&mut self, path: &std::path::Path) -> std::io::Result<()> {
+        self.input_recorder = Some(InputRecorder::create(path)?);
+        Ok(())
+    }
+    /// replays the input recorded in the file at `path`, instead of the live input. see [`InputReplay`]
+    /// live input is ignored until the replay is finished. The window still reacts to the OS though (resize, close etc..).
+    #[cfg(feature = "input_recording")]
+    pub fn replay_input(&mut self, path: &std::path::Path) -> std::io::Result<()> {
+        self.input_replay = Some(InputReplay::load(path)?);
+        Ok(())
+    }
     pub fn enter_event_loop(mut self) {
         // polls for events and returns if there's some activity.
         // But if there is no event for the specified duration, it will return anyway.
         // used by "reactive" apps which don't do anything unless there's some event.
         tracing::info!("entering glfw event loop");
         let mut wait_events_duration = std::time::Duration::ZERO;
+        // when egui wants the next repaint. `None` means no repaint is needed unless there's some input.
+        let mut next_repaint = Some(Instant::now());
+        // other threads can request a repaint using the egui context (eg: when a background task finishes).
+        // But we might be blocked waiting for events. So, we wake up the event loop and schedule a repaint.
+        let repaint_requested = Arc::new(AtomicBool::new(false));
+        // the pass number of the egui pass which is running right now. `u64::MAX` when no pass is running.
+        // requests made during a pass (eg: by animations) are already part of that pass's repaint delay, so they don't wake us up.
+        let running_pass_nr = Arc::new(AtomicU64::new(u64::MAX));
+        {
+            let repaint_requested = repaint_requested.clone();
+            let running_pass_nr = running_pass_nr.clone();
+            let waker = self.glfw_backend.event_loop_waker();
+            self.egui_context.set_request_repaint_callback(move |info| {
+                if info.delay.is_zero()
+                    && info.current_cumulative_pass_nr != running_pass_nr.load(Ordering::Acquire)
+                {
+                    repaint_requested.store(true, Ordering::Release);
+                    waker.wake();
+                }
+            });
+        }
+        // start of the latest frame. used to throttle the requested repaints while unfocused
+        let mut last_frame_start: Option<Instant> = None;
         let callback = move || {
             let Self {
                 user_data,
                 egui_context,
                 default_gfx_backend,
                 glfw_backend,
+                catch_panics,
+                unfocused_min_frame_time,
+                #[cfg(feature = "persistence")]
+                persistence_path,
+                #[cfg(feature = "event_log")]
+                output_event_recorder,
+                #[cfg(feature = "input_recording")]
+                input_recorder,
+                #[cfg(feature = "input_recording")]
+                input_replay,
             } = &mut self;
             glfw_backend
                 .glfw
@@ -148,25 +600,168 @@ impl<T: EguiOverlay + 'static> OverlayApp<T> {
 
             if glfw_backend.resized_event_pending {
                 let latest_size = glfw_backend.window.get_framebuffer_size();
-                default_gfx_backend.resize_framebuffer([latest_size.0 as _, latest_size.1 as _]);
+                let latest_size = [latest_size.0 as _, latest_size.1 as _];
+                default_gfx_backend.resize_framebuffer(latest_size);
                 glfw_backend.resized_event_pending = false;
+                user_data.on_resize(latest_size, glfw_backend.scale);
             }
-            // run userapp gui function. let user do anything he wants with window or gfx backends
-            if let Some((platform_output, timeout)) =
-                user_data.run(egui_context, default_gfx_backend, glfw_backend)
-            {
-                wait_events_duration = timeout.min(std::time::Duration::from_secs(1));
-                if !platform_output.copied_text.is_empty() {
-                    glfw_backend
-                        .window
-                        .set_clipboard_string(&platform_output.copied_text);
+            // we only run a frame if there's some input or if egui wants a repaint.
+            // otherwise, the previously presented frame is still valid and we can save some power.
+            // the OS wants the window contents redrawn (eg: during live resize). so, we draw and present right now
+            // instead of waiting for egui's next repaint deadline, to avoid showing a stale/black window.
+            let refresh_requested = std::mem::take(&mut glfw_backend.refresh_requested);
+            // while replaying, the recorded input replaces the live input.
+            #[allow(unused_mut)]
+            let mut replayed_frame = false;
+            #[cfg(feature = "input_recording")]
+            if let Some(replay) = input_replay.as_mut() {
+                if let Some(raw_input) = replay.next_due(glfw_backend.time()) {
+                    glfw_backend.raw_input = raw_input;
+                    replayed_frame = true;
+                } else {
+                    glfw_backend.raw_input.events.clear();
                 }
-                glfw_backend.set_cursor(platform_output.cursor_icon);
-            } else {
-                wait_events_duration = std::time::Duration::ZERO;
+                if replay.is_finished() {
+                    tracing::info!("input replay finished");
+                    *input_replay = None;
+                }
+            }
+            // always consume the request, so that it doesn't linger until the next frame.
+            let mut external_repaint = repaint_requested.swap(false, Ordering::Acquire);
+            if external_repaint {
+                // respect the unfocused throttle, like egui's own repaints
+                let mut deadline = Instant::now();
+                if let (Some(min_frame_time), Some(last_frame_start)) = (
+                    unfocused_min_frame_time.filter(|_| !glfw_backend.focused),
+                    last_frame_start,
+                ) {
+                    deadline = deadline.max(last_frame_start + min_frame_time);
+                }
+                next_repaint = Some(next_repaint.map_or(deadline, |next| next.min(deadline)));
+                // while rendering is disabled, repaint deadlines are ignored. So, we run the gui once (without drawing) for this request.
+                external_repaint = !glfw_backend.should_render();
+            }
+            // when rendering is disabled (or the window is hidden), only input or explicit repaint requests (from outside a pass) run the gui (without drawing).
+            // This lets the user re-enable rendering or show the window again (eg: from a tray thread via `request_repaint`).
+            let repaint_wanted = refresh_requested
+                || next_repaint.is_some_and(|deadline| deadline <= Instant::now())
+                || glfw_backend.is_fading();
+            let redraw_requested = (repaint_wanted && glfw_backend.should_render())
+                || external_repaint
+                || replayed_frame
+                || !glfw_backend.frame_events.is_empty()
+                || !glfw_backend.raw_input.events.is_empty();
+            if redraw_requested {
+                let frame_start = Instant::now();
+                last_frame_start = Some(frame_start);
+                #[cfg(feature = "input_recording")]
+                if let Some(recorder) = input_recorder.as_mut() {
+                    if let Err(e) = recorder.record(&glfw_backend.raw_input) {
+                        tracing::error!(%e, "failed to record input. stopping the recording");
+                        *input_recorder = None;
+                    }
+                }
+                // run userapp gui function. let user do anything he wants with window or gfx backends
+                running_pass_nr.store(egui_context.cumulative_pass_nr(), Ordering::Release);
+                let frame = || user_data.run(egui_context, default_gfx_backend, glfw_backend);
+                let frame_output = if *catch_panics {
+                    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(frame)) {
+                        Ok(frame_output) => frame_output,
+                        Err(payload) => {
+                            let panic_message = payload
+                                .downcast_ref::<&str>()
+                                .copied()
+                                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                                .unwrap_or("unknown panic payload");
+                            tracing::error!(
+                                panic_message,
+                                "caught a panic during frame. skipping it"
+                            );
+                            // finish the pass that the panic interrupted (most likely in `gui_run`), so that the next frame doesn't begin on top of it.
+                            // The output is thrown away, except for the texture updates, which egui won't send again (eg: font atlas).
+                            let textures_delta = egui_context.end_pass().textures_delta;
+                            // throw away any half recorded commands or acquired surface image of this frame
+                            #[cfg(feature = "wgpu")]
+                            default_gfx_backend.discard_frame();
+                            default_gfx_backend.upload_textures(textures_delta);
+                            user_data.on_panic(panic_message);
+                            None
+                        }
+                    }
+                } else {
+                    frame()
+                };
+                running_pass_nr.store(u64::MAX, Ordering::Release);
+                if let Some((platform_output, timeout)) = frame_output {
+                    next_repaint = Instant::now().checked_add(timeout);
+                    if !platform_output.copied_text.is_empty() {
+                        glfw_backend
+                            .window
+                            .set_clipboard_string(&platform_output.copied_text);
+                    }
+                    glfw_backend.set_cursor(platform_output.cursor_icon);
+                    let ime_cursor_rect = platform_output.ime.map(|ime| ime.cursor_rect);
+                    if ime_cursor_rect != glfw_backend.ime_cursor_rect {
+                        tracing::trace!(?ime_cursor_rect, "ime cursor rect changed");
+                        glfw_backend.ime_cursor_rect = ime_cursor_rect;
+                    }
+                    #[cfg(feature = "event_log")]
+                    if let Some(recorder) = output_event_recorder.as_mut() {
+                        if let Err(e) =
+                            recorder.record(glfw_backend.time(), &platform_output.events)
+                        {
+                            tracing::error!(%e, "failed to record egui output events. stopping the event log");
+                            *output_event_recorder = None;
+                        }
+                    }
+                    // keep it around, so that the next frame's `gui_run` can react to it (eg: log copied text)
+                    glfw_backend.last_platform_output = Some(platform_output);
+                } else {
+                    next_repaint = Some(Instant::now());
+                }
+                // when unfocused, delay egui's repaints. input events still trigger a frame immediately.
+                if let Some(min_frame_time) =
+                    unfocused_min_frame_time.filter(|_| !glfw_backend.focused)
+                {
+                    next_repaint =
+                        next_repaint.map(|deadline| deadline.max(frame_start + min_frame_time));
+                }
+            }
+            // wait for events until the next repaint, but no longer than a second.
+            // while rendering is disabled, repaint deadlines are ignored. So, we don't want to spin on an expired one.
+            wait_events_duration = next_repaint
+                .filter(|_| glfw_backend.should_render())
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                .unwrap_or(Duration::MAX)
+                .min(Duration::from_secs(1));
+            // when passthrough, we don't get cursor events. so, we poll the cursor to notice when it moves over the content.
+            if glfw_backend.auto_passthrough && glfw_backend.passthrough {
+                wait_events_duration = wait_events_duration.min(Duration::from_millis(16));
+            }
+            // wake up in time for the next replayed frame
+            #[cfg(feature = "input_recording")]
+            if let Some(until_next) = input_replay
+                .as_ref()
+                .and_then(|replay| replay.time_until_next(glfw_backend.time()))
+            {
+                wait_events_duration =
+                    wait_events_duration.min(Duration::from_secs_f64(until_next));
+            }
+            // keep the fade smooth
+            if glfw_backend.is_fading() && glfw_backend.should_render() {
+                wait_events_duration = wait_events_duration.min(Duration::from_millis(16));
             }
             #[cfg(not(target_os = "emscripten"))]
-            glfw_backend.window.should_close()
+            {
+                let should_close = glfw_backend.should_close();
+                #[cfg(feature = "persistence")]
+                if should_close {
+                    if let Some(path) = persistence_path.as_deref() {
+                        save_egui_memory(egui_context, path);
+                    }
+                }
+                should_close
+            }
         };
 
         // on emscripten, just keep calling forever i guess.